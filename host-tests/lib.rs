@@ -0,0 +1,50 @@
+//! Builds the modules of `../src` that depend on neither the ESP32-S2 HAL nor embassy, at the
+//! same module paths as in the firmware, so their `#[cfg(test)]` tests run on the host.
+#![no_std]
+// The firmware is a binary crate, where async functions in its traits are not public API
+#![allow(async_fn_in_trait)]
+
+#[macro_use]
+extern crate alloc;
+
+#[cfg(test)]
+extern crate std;
+
+#[path = "../src/comfort.rs"]
+pub mod comfort;
+
+#[path = "../src/console.rs"]
+pub mod console;
+
+#[path = "../src/moon.rs"]
+pub mod moon;
+
+#[path = "../src/settings"]
+pub mod settings {
+    pub mod record;
+}
+
+#[path = "../src/sun.rs"]
+pub mod sun;
+
+#[path = "../src/storage"]
+pub mod storage {
+    pub mod record;
+}
+
+#[path = "../src/units.rs"]
+pub mod units;
+
+#[path = "../src/weather"]
+pub mod weather {
+    pub mod alert_source;
+    pub mod compare;
+}
+
+#[path = "../src/location"]
+pub mod location {
+    pub mod qualifier;
+    pub mod resolved;
+
+    pub use self::resolved::Location;
+}
@@ -0,0 +1,160 @@
+import math
+
+from PIL import Image
+
+# Append a row of night icons to the 3x3 weather icon sprite sheets:
+#   9  clear night: crescent moon
+#   10 mainly clear night: the mainly clear cloud in front of a crescent moon
+#   11 partly cloudy night: the partly cloudy cloud, moved down, in front of the same moon
+# Palette indices: 0 black, 1 dark grey, 2 light grey (cloud fill), 3 white
+
+# (day sheet, night sheet, icon size, moon outline width, clear moon, moon behind a cloud,
+#  mainly clear cloud fill seed, partly cloudy cloud fill seed and downward shift, cloud outline
+#  width)
+SHEETS = [
+    (
+        "resources/weather_icons_70px_210x210_4b.bmp",
+        "resources/weather_icons_70px_210x280_4b.bmp",
+        70,
+        4.5,
+        (35.0, 35.0, 28.0),
+        (29.5, 26.0, 19.0),
+        (35, 50),
+        (35, 40),
+        12,
+        4,
+    ),
+    (
+        "resources/weather_icons_20px_60x60_4b.bmp",
+        "resources/weather_icons_20px_60x80_4b.bmp",
+        20,
+        1.3,
+        (10.0, 10.0, 8.5),
+        (8.5, 7.0, 5.5),
+        (10, 12),
+        (10, 10),
+        3,
+        2,
+    ),
+]
+
+MAINLY_CLEAR = 1
+PARTLY_CLOUDY = 2
+CLOUD_FILL = 2
+WHITE = 3
+SUPERSAMPLE = 4
+
+
+def on_crescent_outline(x, y, cx, cy, r, width):
+    """Whether (x, y) lies on the outline of a moon disk with a bite taken out of its upper right"""
+    to_limb = r - math.hypot(x - cx, y - cy)
+    to_terminator = math.hypot(x - cx - 0.55 * r, y - cy + 0.35 * r) - 0.8 * r
+    return 0 <= min(to_limb, to_terminator) < width
+
+
+def shade(coverage):
+    """Palette index for the fraction of a pixel covered by the outline"""
+    if coverage > 0.75:
+        return 0
+    if coverage > 0.45:
+        return 1
+    if coverage > 0.15:
+        return 2
+    return WHITE
+
+
+def draw_moon(icon, moon, width):
+    cx, cy, r = moon
+    samples = [(i + 0.5) / SUPERSAMPLE for i in range(SUPERSAMPLE)]
+    for y in range(icon.size[1]):
+        for x in range(icon.size[0]):
+            hits = sum(
+                on_crescent_outline(x + dx, y + dy, cx, cy, r, width)
+                for dx in samples
+                for dy in samples
+            )
+            color = shade(hits / SUPERSAMPLE**2)
+            # lower indices are darker, and the darker color wins
+            if color < icon.getpixel((x, y)):
+                icon.putpixel((x, y), color)
+
+
+def cloud_pixels(icon, seed, outline):
+    """Pixels of the cloud: its connected fill grown by the outline width"""
+    width, height = icon.size
+    fill = set()
+    stack = [seed]
+    while stack:
+        x, y = stack.pop()
+        if (x, y) in fill or not (0 <= x < width and 0 <= y < height):
+            continue
+        if icon.getpixel((x, y)) != CLOUD_FILL:
+            continue
+        fill.add((x, y))
+        stack += [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+
+    cloud = set(fill)
+    for _ in range(outline):
+        cloud |= {
+            (x + dx, y + dy)
+            for x, y in cloud
+            for dx in (-1, 0, 1)
+            for dy in (-1, 0, 1)
+            if 0 <= x + dx < width
+            and 0 <= y + dy < height
+            and icon.getpixel((x + dx, y + dy)) != WHITE
+        }
+    return cloud
+
+
+def moon_behind_cloud(day, index, size, moon, width, seed, outline, shift=0):
+    """Crescent moon partly hidden by the cloud of day icon `index`, moved down by `shift`"""
+    cloudy = day.crop((index * size, 0, (index + 1) * size, size))
+    icon = Image.new("P", (size, size), WHITE)
+    draw_moon(icon, moon, width)
+    for x, y in cloud_pixels(cloudy, seed, outline):
+        if y + shift < size:
+            icon.putpixel((x, y + shift), cloudy.getpixel((x, y)))
+    return icon
+
+
+for (
+    day_path,
+    night_path,
+    size,
+    width,
+    clear_moon,
+    cloudy_moon,
+    mainly_clear_seed,
+    partly_cloudy_seed,
+    partly_cloudy_shift,
+    outline,
+) in SHEETS:
+    day = Image.open(day_path)
+    sheet = Image.new("P", (day.size[0], day.size[1] + size), WHITE)
+    sheet.putpalette(day.getpalette())
+    sheet.paste(day, (0, 0))
+
+    clear_night = Image.new("P", (size, size), WHITE)
+    draw_moon(clear_night, clear_moon, width)
+    sheet.paste(clear_night, (0, 3 * size))
+
+    mainly_clear_night = moon_behind_cloud(
+        day, MAINLY_CLEAR, size, cloudy_moon, width, mainly_clear_seed, outline
+    )
+    sheet.paste(mainly_clear_night, (size, 3 * size))
+
+    partly_cloudy_night = moon_behind_cloud(
+        day,
+        PARTLY_CLOUDY,
+        size,
+        cloudy_moon,
+        width,
+        partly_cloudy_seed,
+        outline,
+        partly_cloudy_shift,
+    )
+    sheet.paste(partly_cloudy_night, (2 * size, 3 * size))
+
+    sheet.save(night_path)
+    print(f"Wrote {night_path} ({sheet.size[0]}x{sheet.size[1]})")
@@ -0,0 +1,249 @@
+use libm::{fabsf, logf, powf, sqrtf};
+
+use crate::units::{Speed, SpeedUnit, Temperature, TemperatureUnit};
+
+// Magnus formula coefficients over water (Alduchov and Eskridge)
+const MAGNUS_B: f32 = 17.625;
+const MAGNUS_C: f32 = 243.04;
+
+/// Heat index below which the simple Steadman estimate is used (NOAA)
+const HEAT_INDEX_REGRESSION_F: f32 = 80.0;
+// Wind chill is only defined at or below 50 °F and from 3 mph (NWS)
+const WIND_CHILL_MAX_F: f32 = 50.0;
+const WIND_CHILL_MIN_MPH: f32 = 3.0;
+
+// Comfort class limits on the feels-like temperature and the dew point, in °C
+const VERY_COLD_C: f32 = -18.0;
+const COLD_C: f32 = 5.0;
+const COOL_C: f32 = 16.0;
+/// NWS heat index "extreme caution" (90 °F)
+const HOT_C: f32 = 32.0;
+/// NWS heat index "danger" (103 °F)
+const VERY_HOT_C: f32 = 39.5;
+/// Dew point above which the air feels sticky (65 °F)
+const MUGGY_DEW_POINT_C: f32 = 18.0;
+
+/// Dew point in °C for an air temperature in °C and relative humidity in percent, using the
+/// Magnus formula.
+pub fn dew_point_c(temperature_c: f32, relative_humidity: f32) -> f32 {
+    // Completely dry air would take the log of zero
+    let humidity = relative_humidity.clamp(1.0, 100.0) / 100.0;
+    let gamma = logf(humidity) + MAGNUS_B * temperature_c / (MAGNUS_C + temperature_c);
+    MAGNUS_C * gamma / (MAGNUS_B - gamma)
+}
+
+/// NOAA heat index in °F for an air temperature in °F and relative humidity in percent.
+///
+/// Uses the Rothfusz regression with the NOAA adjustments for very dry and very humid air, and
+/// the simple Steadman estimate when that is below 80 °F.
+pub fn heat_index_f(temperature_f: f32, relative_humidity: f32) -> f32 {
+    let (t, rh) = (temperature_f, relative_humidity);
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    if (simple + t) / 2.0 < HEAT_INDEX_REGRESSION_F {
+        return simple;
+    }
+
+    let mut index = -42.379 + 2.049_015_2 * t + 10.143_331 * rh
+        - 0.224_755_4 * t * rh
+        - 6.837_83e-3 * t * t
+        - 5.481_717e-2 * rh * rh
+        + 1.228_74e-3 * t * t * rh
+        + 8.5282e-4 * t * rh * rh
+        - 1.99e-6 * t * t * rh * rh;
+    if rh < 13.0 && (80.0..=112.0).contains(&t) {
+        index -= (13.0 - rh) / 4.0 * sqrtf((17.0 - fabsf(t - 95.0)) / 17.0);
+    } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+        index += (rh - 85.0) / 10.0 * ((87.0 - t) / 5.0);
+    }
+    index
+}
+
+/// NWS wind chill in °F for an air temperature in °F and wind speed in mph.
+///
+/// Returns the air temperature above 50 °F or below 3 mph, where wind chill is not defined.
+pub fn wind_chill_f(temperature_f: f32, wind_mph: f32) -> f32 {
+    if temperature_f > WIND_CHILL_MAX_F || wind_mph < WIND_CHILL_MIN_MPH {
+        return temperature_f;
+    }
+    let wind = powf(wind_mph, 0.16);
+    35.74 + 0.6215 * temperature_f - 35.75 * wind + 0.4275 * temperature_f * wind
+}
+
+/// How the conditions feel, ordered from coldest to hottest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Comfort {
+    VeryCold,
+    Cold,
+    Cool,
+    Comfortable,
+    /// Comfortable or warm, but with a high dew point
+    Muggy,
+    Hot,
+    VeryHot,
+}
+
+impl Comfort {
+    /// Classify a feels-like temperature and dew point, both in °C
+    pub fn classify(feels_like_c: f32, dew_point_c: f32) -> Self {
+        match feels_like_c {
+            t if t < VERY_COLD_C => Comfort::VeryCold,
+            t if t < COLD_C => Comfort::Cold,
+            t if t < COOL_C => Comfort::Cool,
+            t if t >= VERY_HOT_C => Comfort::VeryHot,
+            t if t >= HOT_C => Comfort::Hot,
+            _ if dew_point_c >= MUGGY_DEW_POINT_C => Comfort::Muggy,
+            _ => Comfort::Comfortable,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Comfort::VeryCold => "very cold",
+            Comfort::Cold => "cold",
+            Comfort::Cool => "cool",
+            Comfort::Comfortable => "comfortable",
+            Comfort::Muggy => "muggy",
+            Comfort::Hot => "hot",
+            Comfort::VeryHot => "very hot",
+        }
+    }
+}
+
+/// Comfort metrics derived from the temperature, humidity and wind of one set of conditions
+#[derive(Debug, Clone, Copy)]
+pub struct ComfortMetrics {
+    pub dew_point: Temperature,
+    pub heat_index: Temperature,
+    pub wind_chill: Temperature,
+    /// Wind chill when cold, heat index when warm, the air temperature otherwise
+    pub feels_like: Temperature,
+    pub comfort: Comfort,
+}
+
+impl ComfortMetrics {
+    /// Metrics for `temperature`, `relative_humidity` in percent and `wind_speed`, with the
+    /// temperatures in the unit of `temperature`
+    pub fn new(temperature: Temperature, relative_humidity: f32, wind_speed: Speed) -> Self {
+        let celsius = temperature.to(TemperatureUnit::Celsius).value;
+        let fahrenheit = temperature.to(TemperatureUnit::Fahrenheit).value;
+        let mph = wind_speed.to(SpeedUnit::MilesPerHour).value;
+
+        let dew_point_c = dew_point_c(celsius, relative_humidity);
+        let heat_index_f = heat_index_f(fahrenheit, relative_humidity);
+        let wind_chill_f = wind_chill_f(fahrenheit, mph);
+        let feels_like_f = if fahrenheit <= WIND_CHILL_MAX_F {
+            wind_chill_f
+        } else if heat_index_f >= HEAT_INDEX_REGRESSION_F {
+            heat_index_f
+        } else {
+            fahrenheit
+        };
+
+        let in_unit =
+            |value: f32, unit: TemperatureUnit| Temperature::new(value, unit).to(temperature.unit);
+        let feels_like = in_unit(feels_like_f, TemperatureUnit::Fahrenheit);
+        ComfortMetrics {
+            dew_point: in_unit(dew_point_c, TemperatureUnit::Celsius),
+            heat_index: in_unit(heat_index_f, TemperatureUnit::Fahrenheit),
+            wind_chill: in_unit(wind_chill_f, TemperatureUnit::Fahrenheit),
+            feels_like,
+            comfort: Comfort::classify(feels_like.to(TemperatureUnit::Celsius).value, dew_point_c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: f32, expected: f32, tolerance: f32) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} is not within {tolerance} of {expected}"
+        );
+    }
+
+    #[test]
+    fn heat_index_matches_nws_chart() {
+        // (°F, % RH, chart °F)
+        for (t, rh, expected) in [
+            (90.0, 50.0, 95.0),
+            (100.0, 40.0, 109.0),
+            (96.0, 65.0, 121.0),
+        ] {
+            assert_near(heat_index_f(t, rh), expected, 0.5);
+        }
+    }
+
+    #[test]
+    fn heat_index_adjusts_for_very_dry_and_very_humid_air() {
+        // Rothfusz regression with the NWS adjustments, evaluated in double precision
+        assert_near(heat_index_f(95.0, 10.0), 89.45, 0.05);
+        assert_near(heat_index_f(110.0, 10.0), 104.39, 0.05);
+        assert_near(heat_index_f(84.0, 100.0), 103.56, 0.05);
+        assert_near(heat_index_f(80.0, 95.0), 87.80, 0.05);
+    }
+
+    #[test]
+    fn heat_index_uses_steadman_estimate_below_80f() {
+        assert_near(heat_index_f(78.0, 40.0), 77.38, 0.01);
+        assert_near(heat_index_f(70.0, 80.0), 70.46, 0.01);
+        // Averaging to 80 °F or more switches to the regression
+        assert_near(heat_index_f(80.0, 60.0), 81.81, 0.05);
+    }
+
+    #[test]
+    fn wind_chill_matches_nws_chart() {
+        // (°F, mph, chart °F)
+        for (t, wind, expected) in [
+            (40.0, 5.0, 36.0),
+            (30.0, 10.0, 21.0),
+            (0.0, 15.0, -19.0),
+            (-20.0, 30.0, -53.0),
+            (5.0, 60.0, -26.0),
+        ] {
+            assert_near(wind_chill_f(t, wind), expected, 0.5);
+        }
+    }
+
+    #[test]
+    fn wind_chill_is_air_temperature_outside_its_range() {
+        assert_eq!(wind_chill_f(55.0, 20.0), 55.0);
+        assert_eq!(wind_chill_f(20.0, 2.0), 20.0);
+    }
+
+    #[test]
+    fn dew_point_matches_nws_calculator() {
+        // (°C, % RH, °C)
+        for (t, rh, expected) in [
+            (30.0, 50.0, 18.4),
+            (20.0, 60.0, 12.0),
+            (25.0, 60.0, 16.7),
+            (0.0, 100.0, 0.0),
+            (-10.0, 50.0, -18.5),
+        ] {
+            assert_near(dew_point_c(t, rh), expected, 0.1);
+        }
+        assert!(dew_point_c(20.0, 0.0).is_finite());
+    }
+
+    #[test]
+    fn feels_like_and_comfort_follow_the_conditions() {
+        let muggy = ComfortMetrics::new(
+            Temperature::new(27.0, TemperatureUnit::Celsius),
+            80.0,
+            Speed::new(5.0, SpeedUnit::KilometresPerHour),
+        );
+        assert_eq!(muggy.feels_like.unit, TemperatureUnit::Celsius);
+        assert!(muggy.feels_like.value > 27.0);
+        assert_eq!(muggy.comfort, Comfort::Muggy);
+
+        let windy = ComfortMetrics::new(
+            Temperature::new(0.0, TemperatureUnit::Fahrenheit),
+            50.0,
+            Speed::new(15.0, SpeedUnit::MilesPerHour),
+        );
+        assert_near(windy.feels_like.value, -19.0, 0.5);
+        assert_eq!(windy.comfort, Comfort::VeryCold);
+    }
+}
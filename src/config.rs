@@ -1,3 +1,13 @@
+use crate::{
+    location::{Coordinates, LocationSource, SavedLocation},
+    units::{LengthUnit, PressureUnit, SpeedUnit, TemperatureUnit},
+    weather::alert_source::AlertSource,
+};
+
+// The WiFi credentials, network timeouts, forecast days and hours, display units and alert
+// source below are the defaults of the runtime settings (see settings/mod.rs), used until
+// settings are saved
+
 // Network timeouts
 pub const NETWORK_LINK_TIMEOUT_SECS: u64 = 30;
 pub const NETWORK_IP_TIMEOUT_SECS: u64 = 45;
@@ -6,17 +16,85 @@ pub const NETWORK_IP_TIMEOUT_SECS: u64 = 45;
 pub const WIFI_SSID: &str = env!("WIFI_SSID");
 pub const WIFI_PASSWORD: &str = env!("WIFI_PASSWORD");
 
+// Saved forecast locations. The active one is switched with the front buttons (D next,
+// A previous) and kept across deep sleep. Each source is Coordinates(..),
+// PlaceName("Boulder, CO") or Auto(..) with fallback coordinates. Place names and Auto
+// locations are resolved once and cached in flash. An empty name shows the resolved place.
+pub const LOCATIONS: &[SavedLocation] = &[
+    SavedLocation {
+        name: "Home",
+        source: LocationSource::Coordinates(Coordinates {
+            latitude: "39.868",
+            longitude: "-104.9719",
+            timezone: "America/Denver",
+        }),
+    },
+    SavedLocation {
+        name: "Office",
+        source: LocationSource::PlaceName("Boulder, CO"),
+    },
+    SavedLocation {
+        name: "",
+        source: LocationSource::Auto(Coordinates {
+            latitude: "39.868",
+            longitude: "-104.9719",
+            timezone: "America/Denver",
+        }),
+    },
+];
+
+// Show one summary row for each of the first five saved locations, fetched in a single
+// request, instead of the full forecast for the active location
+pub const MULTI_LOCATION_SUMMARY: bool = false;
+
+// IP geolocation endpoint for LocationSource::Auto (plain HTTP)
+pub const IP_GEOLOCATION_HOST: &str = "ip-api.com";
+pub const IP_GEOLOCATION_PATH: &str = "/json/?fields=status,city,regionName,lat,lon,timezone";
+
 // Open-Meteo API weather arguments
-pub const OPENMETEO_LATITUDE: &str = "39.868";
-pub const OPENMETEO_LONGITUDE: &str = "-104.9719";
-pub const OPENMETEO_TIMEZONE: &str = "America/Denver";
 pub const OPENMETEO_TEMP_UNIT: &str = "fahrenheit"; // fahrenheit or celsius
 pub const OPENMETEO_WIND_UNIT: &str = "mph"; // mph, kmh
+pub const OPENMETEO_PRECIP_UNIT: &str = "inch"; // inch or mm
+pub const OPENMETEO_FORECAST_HOURS: usize = 24; // 1 to 48
+pub const OPENMETEO_FORECAST_DAYS: usize = 7; // 1 to 16, today included
+
+// Units shown on the display, converted on the device from the units fetched above
+pub const DISPLAY_TEMP_UNIT: TemperatureUnit = TemperatureUnit::Fahrenheit; // Fahrenheit or Celsius
+pub const DISPLAY_WIND_UNIT: SpeedUnit = SpeedUnit::MilesPerHour; // MilesPerHour, KilometresPerHour, MetresPerSecond or Knots
+pub const DISPLAY_PRECIP_UNIT: LengthUnit = LengthUnit::Inch; // Inch, Millimetre or Centimetre
+pub const DISPLAY_PRESSURE_UNIT: PressureUnit = PressureUnit::InchOfMercury; // InchOfMercury or Hectopascal
+
+// Open-Meteo air quality arguments
+pub const AIR_QUALITY_INDEX: &str = "us"; // us or european
+
+// Severe weather alerts: Disabled, Nws (US) or MeteoAlarm (Europe). Both services only serve
+// HTTPS, so they need a plain HTTP proxy in place of the hosts below.
+pub const ALERT_SOURCE: AlertSource = AlertSource::Disabled;
+pub const NWS_ALERTS_HOST: &str = "api.weather.gov";
+pub const NWS_USER_AGENT: &str = "(magtag-weatherstation, contact@example.com)";
+pub const METEOALARM_HOST: &str = "feeds.meteoalarm.org";
+pub const METEOALARM_COUNTRY: &str = "austria"; // feed name, e.g. "germany" or "united-kingdom"
+pub const METEOALARM_REGION: &str = "Wien"; // area name (areaDesc) for warnings without a polygon
 
 // deep sleep constants
 pub const SLEEP_ON_ERROR_SECS: u64 = 60 * 5;
+// Longest sleep while a severe or extreme alert is active, so updates show up sooner
+pub const SLEEP_DURING_ALERT_SECS: u64 = 60 * 60 * 2;
+
+// Serial console: an open session goes idle and lets the device sleep after this long without
+// input
+pub const CONSOLE_IDLE_SECS: u64 = 60 * 2;
+
+// Heap size in bytes
+pub const HEAP_SIZE: usize = 64000;
+// Heap kept free while a response is read: the WiFi driver's 10 static 1600 byte RX buffers,
+// its dynamic TX and RX buffers, the 2KB socket buffers and the request
+const HEAP_RESERVED: usize = 24 * 1024;
 
 // Request constants:
+// Longest HTTP response read, headers included. The response buffer is allocated once at
+// this size, so it takes the heap left over by everything else during a fetch.
+pub const MAX_RESPONSE_LEN: usize = HEAP_SIZE - HEAP_RESERVED;
 pub const RESOLVE_TIMEOUT: embassy_time::Duration = embassy_time::Duration::from_secs(10);
 pub const CONNECT_TIMEOUT: embassy_time::Duration = embassy_time::Duration::from_secs(10);
 pub const REQUEST_TIMEOUT: embassy_time::Duration = embassy_time::Duration::from_secs(10);
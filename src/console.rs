@@ -0,0 +1,714 @@
+use core::fmt::{Display, Write};
+
+use heapless::String;
+
+use crate::units::{LengthUnit, PressureUnit, SpeedUnit, TemperatureUnit};
+
+/// Longest command line accepted, longer input is dropped
+pub const LINE_LEN: usize = 128;
+
+const HELP: &str = "\
+help                 show this help
+config               show all settings
+get <key>            show one setting
+set <key> <value>    change a setting and save it to flash
+fetch                fetch the forecast now and print it
+forecast             print the last forecast fetched
+errors               show the recent errors
+sleep <seconds>      enter deep sleep
+Location: <latitude>,<longitude>,<timezone>[,<name>] or none
+Units: celsius/fahrenheit, kmh/ms/mph/kn, mm/cm/inch, hPa/inHg
+Alert source: disabled, nws or meteoalarm
+WiFi and timeout changes take effect on the next wake
+";
+
+/// Device actions behind the console commands
+pub trait Device {
+    type Settings: Settings;
+    /// Forecast as printed by `fetch` and `forecast`
+    type Forecast: Display;
+    type Error: Display;
+
+    /// Settings in effect
+    fn settings(&self) -> Self::Settings;
+
+    /// Save `settings` to flash and put them in effect
+    async fn save_settings(&mut self, settings: Self::Settings) -> Result<(), Self::Error>;
+
+    /// Fetch the forecast for the active location
+    async fn fetch(&mut self) -> Result<Self::Forecast, Self::Error>;
+
+    /// Last forecast fetched successfully for the active location
+    async fn saved_forecast(&mut self) -> Option<Self::Forecast>;
+
+    /// Number and message of the recent errors, oldest first
+    fn error_history(&self) -> impl IntoIterator<Item = (u32, impl Display)>;
+
+    /// Enter deep sleep for `secs` seconds
+    fn sleep(&mut self, secs: u64);
+}
+
+/// Runtime settings as shown and changed on the console
+pub trait Settings {
+    /// Current value of `key`
+    fn get(&self, key: Key) -> Value<'_>;
+
+    /// Change `key` to `value`, as returned by `Key::parse_value` for the same key. Ranges are
+    /// left to the device when saving.
+    fn set(&mut self, key: Key, value: Value) -> Result<(), CommandError>;
+}
+
+/// Value of a setting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value<'a> {
+    /// Text as typed, for the WiFi credentials and the alert source name
+    Text(&'a str),
+    /// `None` when no location is set
+    Location(Option<LocationValue<'a>>),
+    Temperature(TemperatureUnit),
+    Wind(SpeedUnit),
+    Precip(LengthUnit),
+    Pressure(PressureUnit),
+    /// Forecast lengths and network timeouts
+    Number(u16),
+}
+
+/// Location setting, with the coordinates as typed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocationValue<'a> {
+    pub latitude: &'a str,
+    pub longitude: &'a str,
+    pub timezone: &'a str,
+    /// Place name shown in the header, may be empty
+    pub name: &'a str,
+}
+
+/// A parsed console command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command<'a> {
+    Help,
+    /// Show every setting
+    Config,
+    Get(Key),
+    /// Change a setting to the value as typed
+    Set(Key, &'a str),
+    Fetch,
+    Forecast,
+    Errors,
+    /// Deep sleep for the given number of seconds
+    Sleep(u64),
+}
+
+/// Setting that can be shown and changed on the console
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    WifiSsid,
+    WifiPassword,
+    Location,
+    TemperatureUnit,
+    WindUnit,
+    PrecipUnit,
+    PressureUnit,
+    ForecastDays,
+    ForecastHours,
+    LinkTimeoutSecs,
+    IpTimeoutSecs,
+    AlertSource,
+}
+
+/// Reason a command line is refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CommandError {
+    #[error("unknown command, see \"help\"")]
+    UnknownCommand,
+
+    #[error("unknown setting, see \"config\"")]
+    UnknownKey,
+
+    #[error("missing argument")]
+    MissingArgument,
+
+    #[error("invalid value")]
+    InvalidValue,
+}
+
+impl<'a> Command<'a> {
+    /// Parse a command line, `None` for a blank one
+    pub fn parse(line: &'a str) -> Result<Option<Self>, CommandError> {
+        let line = line.trim();
+        let (name, args) = line
+            .split_once(' ')
+            .map_or((line, ""), |(name, args)| (name, args.trim()));
+        let command = match name {
+            "" => return Ok(None),
+            "help" => Command::Help,
+            "config" => Command::Config,
+            "get" => Command::Get(Key::parse(args)?),
+            "set" => {
+                let (key, value) = args.split_once(' ').ok_or(CommandError::MissingArgument)?;
+                Command::Set(Key::parse(key)?, value.trim())
+            }
+            "fetch" => Command::Fetch,
+            "forecast" => Command::Forecast,
+            "errors" => Command::Errors,
+            "sleep" if args.is_empty() => return Err(CommandError::MissingArgument),
+            "sleep" => Command::Sleep(
+                args.parse()
+                    .ok()
+                    .filter(|&secs| secs > 0)
+                    .ok_or(CommandError::InvalidValue)?,
+            ),
+            _ => return Err(CommandError::UnknownCommand),
+        };
+        Ok(Some(command))
+    }
+}
+
+impl Key {
+    const ALL: [Key; 12] = [
+        Key::WifiSsid,
+        Key::WifiPassword,
+        Key::Location,
+        Key::TemperatureUnit,
+        Key::WindUnit,
+        Key::PrecipUnit,
+        Key::PressureUnit,
+        Key::ForecastDays,
+        Key::ForecastHours,
+        Key::LinkTimeoutSecs,
+        Key::IpTimeoutSecs,
+        Key::AlertSource,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Key::WifiSsid => "wifi_ssid",
+            Key::WifiPassword => "wifi_password",
+            Key::Location => "location",
+            Key::TemperatureUnit => "temperature_unit",
+            Key::WindUnit => "wind_unit",
+            Key::PrecipUnit => "precip_unit",
+            Key::PressureUnit => "pressure_unit",
+            Key::ForecastDays => "forecast_days",
+            Key::ForecastHours => "forecast_hours",
+            Key::LinkTimeoutSecs => "link_timeout_secs",
+            Key::IpTimeoutSecs => "ip_timeout_secs",
+            Key::AlertSource => "alert_source",
+        }
+    }
+
+    fn parse(name: &str) -> Result<Self, CommandError> {
+        match name {
+            "" => Err(CommandError::MissingArgument),
+            _ => Key::ALL
+                .into_iter()
+                .find(|key| key.name() == name)
+                .ok_or(CommandError::UnknownKey),
+        }
+    }
+
+    /// Parse `value` as typed for this setting
+    pub fn parse_value(self, value: &str) -> Result<Value<'_>, CommandError> {
+        let invalid = CommandError::InvalidValue;
+        Ok(match self {
+            Key::WifiSsid | Key::WifiPassword | Key::AlertSource => Value::Text(value),
+            Key::Location if value == "none" => Value::Location(None),
+            Key::Location => {
+                let mut parts = value.splitn(4, ',').map(str::trim);
+                let (Some(latitude), Some(longitude), Some(timezone)) =
+                    (parts.next(), parts.next(), parts.next())
+                else {
+                    return Err(CommandError::MissingArgument);
+                };
+                let in_range = |coordinate: &str, limit: f32| {
+                    coordinate
+                        .parse::<f32>()
+                        .is_ok_and(|coordinate| coordinate.abs() <= limit)
+                };
+                if !in_range(latitude, 90.0) || !in_range(longitude, 180.0) || timezone.is_empty() {
+                    return Err(invalid);
+                }
+                Value::Location(Some(LocationValue {
+                    latitude,
+                    longitude,
+                    timezone,
+                    name: parts.next().unwrap_or_default(),
+                }))
+            }
+            Key::TemperatureUnit => {
+                Value::Temperature(TemperatureUnit::parse(value).ok_or(invalid)?)
+            }
+            Key::WindUnit => Value::Wind(SpeedUnit::parse(value).ok_or(invalid)?),
+            Key::PrecipUnit => Value::Precip(LengthUnit::parse(value).ok_or(invalid)?),
+            Key::PressureUnit => Value::Pressure(PressureUnit::parse(value).ok_or(invalid)?),
+            Key::ForecastDays | Key::ForecastHours | Key::LinkTimeoutSecs | Key::IpTimeoutSecs => {
+                Value::Number(value.parse().map_err(|_| invalid)?)
+            }
+        })
+    }
+
+    /// Write `value` of this setting in the form `set` accepts, except for the password
+    fn write_value(self, value: Value, out: &mut impl Write) -> core::fmt::Result {
+        match value {
+            Value::Text("") if self == Key::WifiPassword => Ok(()),
+            Value::Text(_) if self == Key::WifiPassword => out.write_str("********"),
+            Value::Text(text) => out.write_str(text),
+            Value::Location(Some(location)) => write!(
+                out,
+                "{},{},{},{}",
+                location.latitude, location.longitude, location.timezone, location.name
+            ),
+            Value::Location(None) => out.write_str("none"),
+            Value::Temperature(unit) => out.write_str(match unit {
+                TemperatureUnit::Celsius => "celsius",
+                TemperatureUnit::Fahrenheit => "fahrenheit",
+            }),
+            Value::Wind(unit) => out.write_str(unit.symbol()),
+            Value::Precip(unit) => out.write_str(match unit {
+                LengthUnit::Millimetre => "mm",
+                LengthUnit::Centimetre => "cm",
+                LengthUnit::Inch => "inch",
+            }),
+            Value::Pressure(unit) => out.write_str(match unit {
+                PressureUnit::Hectopascal => "hPa",
+                PressureUnit::InchOfMercury => "inHg",
+            }),
+            Value::Number(number) => write!(out, "{}", number),
+        }
+    }
+}
+
+/// Run the command on `line` against `device`, writing its output to `out`
+pub async fn execute(
+    line: &str,
+    device: &mut impl Device,
+    out: &mut impl Write,
+) -> core::fmt::Result {
+    let command = match Command::parse(line) {
+        Ok(Some(command)) => command,
+        Ok(None) => return Ok(()),
+        Err(e) => return write_error(out, e),
+    };
+    match command {
+        Command::Help => out.write_str(HELP),
+        Command::Config => {
+            let settings = device.settings();
+            for key in Key::ALL {
+                write!(out, "{} = ", key.name())?;
+                key.write_value(settings.get(key), out)?;
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+        Command::Get(key) => {
+            key.write_value(device.settings().get(key), out)?;
+            writeln!(out)
+        }
+        Command::Set(key, value) => {
+            let mut settings = device.settings();
+            if let Err(e) = key
+                .parse_value(value)
+                .and_then(|value| settings.set(key, value))
+            {
+                return write_error(out, e);
+            }
+            match device.save_settings(settings).await {
+                Ok(()) => writeln!(out, "ok"),
+                Err(e) => write_error(out, e),
+            }
+        }
+        Command::Fetch => match device.fetch().await {
+            Ok(forecast) => write!(out, "{}", forecast),
+            Err(e) => write_error(out, e),
+        },
+        Command::Forecast => match device.saved_forecast().await {
+            Some(forecast) => write!(out, "{}", forecast),
+            None => writeln!(out, "no forecast saved"),
+        },
+        Command::Errors => {
+            let mut history = device.error_history().into_iter().peekable();
+            if history.peek().is_none() {
+                return writeln!(out, "no errors recorded");
+            }
+            for (number, message) in history {
+                writeln!(out, "#{} {}", number, message)?;
+            }
+            Ok(())
+        }
+        Command::Sleep(secs) => {
+            writeln!(out, "sleeping for {} seconds", secs)?;
+            device.sleep(secs);
+            Ok(())
+        }
+    }
+}
+
+fn write_error(out: &mut impl Write, error: impl Display) -> core::fmt::Result {
+    writeln!(out, "error: {}", error)
+}
+
+/// What a byte received on the console did to the line being typed
+#[derive(Debug, PartialEq, Eq)]
+pub enum Input {
+    /// A character was added and should be echoed
+    Added(char),
+    /// The last character was erased
+    Erased,
+    /// The line was finished with a carriage return or line feed
+    Line(String<LINE_LEN>),
+    Ignored,
+}
+
+/// Assembles the bytes received on the console into command lines
+#[derive(Debug, Default)]
+pub struct LineBuffer {
+    line: String<LINE_LEN>,
+    /// The line feed of a CR LF pair does not finish another, empty line
+    after_cr: bool,
+}
+
+impl LineBuffer {
+    pub fn push(&mut self, byte: u8) -> Input {
+        let after_cr = core::mem::replace(&mut self.after_cr, byte == b'\r');
+        match byte {
+            b'\n' if after_cr => Input::Ignored,
+            b'\r' | b'\n' => Input::Line(core::mem::take(&mut self.line)),
+            // Backspace or delete
+            0x08 | 0x7f if self.line.pop().is_some() => Input::Erased,
+            0x20..=0x7e if self.line.push(byte as char).is_ok() => Input::Added(byte as char),
+            _ => Input::Ignored,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use heapless::Vec;
+
+    use super::*;
+
+    /// Run `future` to completion, for the fake device whose futures are always ready
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct TestLocation {
+        latitude: String<16>,
+        longitude: String<16>,
+        timezone: String<32>,
+        name: String<32>,
+    }
+
+    #[derive(Debug, Clone)]
+    struct TestSettings {
+        wifi_ssid: String<32>,
+        wifi_password: String<64>,
+        location: Option<TestLocation>,
+        temperature: TemperatureUnit,
+        wind_speed: SpeedUnit,
+        precipitation: LengthUnit,
+        pressure: PressureUnit,
+        forecast_days: u8,
+        forecast_hours: u8,
+        link_timeout_secs: u16,
+        ip_timeout_secs: u16,
+        alert_source: &'static str,
+    }
+
+    impl Default for TestSettings {
+        fn default() -> Self {
+            TestSettings {
+                wifi_ssid: String::try_from("home").unwrap(),
+                wifi_password: String::try_from("secret").unwrap(),
+                location: None,
+                temperature: TemperatureUnit::Celsius,
+                wind_speed: SpeedUnit::KilometresPerHour,
+                precipitation: LengthUnit::Millimetre,
+                pressure: PressureUnit::Hectopascal,
+                forecast_days: 5,
+                forecast_hours: 12,
+                link_timeout_secs: 30,
+                ip_timeout_secs: 20,
+                alert_source: "disabled",
+            }
+        }
+    }
+
+    impl Settings for TestSettings {
+        fn get(&self, key: Key) -> Value<'_> {
+            match key {
+                Key::WifiSsid => Value::Text(&self.wifi_ssid),
+                Key::WifiPassword => Value::Text(&self.wifi_password),
+                Key::Location => {
+                    Value::Location(self.location.as_ref().map(|location| LocationValue {
+                        latitude: &location.latitude,
+                        longitude: &location.longitude,
+                        timezone: &location.timezone,
+                        name: &location.name,
+                    }))
+                }
+                Key::TemperatureUnit => Value::Temperature(self.temperature),
+                Key::WindUnit => Value::Wind(self.wind_speed),
+                Key::PrecipUnit => Value::Precip(self.precipitation),
+                Key::PressureUnit => Value::Pressure(self.pressure),
+                Key::ForecastDays => Value::Number(self.forecast_days.into()),
+                Key::ForecastHours => Value::Number(self.forecast_hours.into()),
+                Key::LinkTimeoutSecs => Value::Number(self.link_timeout_secs),
+                Key::IpTimeoutSecs => Value::Number(self.ip_timeout_secs),
+                Key::AlertSource => Value::Text(self.alert_source),
+            }
+        }
+
+        fn set(&mut self, key: Key, value: Value) -> Result<(), CommandError> {
+            fn text<const N: usize>(value: &str) -> Result<String<N>, CommandError> {
+                String::try_from(value).map_err(|_| CommandError::InvalidValue)
+            }
+
+            let invalid = CommandError::InvalidValue;
+            match (key, value) {
+                (Key::WifiSsid, Value::Text(ssid)) => self.wifi_ssid = text(ssid)?,
+                (Key::Location, Value::Location(location)) => {
+                    self.location = match location {
+                        Some(location) => Some(TestLocation {
+                            latitude: text(location.latitude)?,
+                            longitude: text(location.longitude)?,
+                            timezone: text(location.timezone)?,
+                            name: text(location.name)?,
+                        }),
+                        None => None,
+                    }
+                }
+                (Key::TemperatureUnit, Value::Temperature(unit)) => self.temperature = unit,
+                (Key::ForecastDays, Value::Number(days)) => {
+                    self.forecast_days = days.try_into().map_err(|_| invalid)?
+                }
+                _ => return Err(invalid),
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct TestDevice {
+        settings: TestSettings,
+        saves: usize,
+        /// Whether saving fails, as with a value out of range
+        reject_save: bool,
+        errors: Vec<(u32, &'static str), 4>,
+        slept: Option<u64>,
+    }
+
+    impl Device for TestDevice {
+        type Settings = TestSettings;
+        type Forecast = &'static str;
+        type Error = &'static str;
+
+        fn settings(&self) -> TestSettings {
+            self.settings.clone()
+        }
+
+        async fn save_settings(&mut self, settings: TestSettings) -> Result<(), &'static str> {
+            if self.reject_save {
+                return Err("setting out of range");
+            }
+            self.settings = settings;
+            self.saves += 1;
+            Ok(())
+        }
+
+        async fn fetch(&mut self) -> Result<&'static str, &'static str> {
+            Err("network connection failed")
+        }
+
+        async fn saved_forecast(&mut self) -> Option<&'static str> {
+            Some("Denver 21.5C\n")
+        }
+
+        fn error_history(&self) -> impl IntoIterator<Item = (u32, impl Display)> {
+            self.errors.clone()
+        }
+
+        fn sleep(&mut self, secs: u64) {
+            self.slept = Some(secs);
+        }
+    }
+
+    fn run(device: &mut TestDevice, line: &str) -> String<1024> {
+        let mut out = String::new();
+        block_on(execute(line, device, &mut out)).unwrap();
+        out
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(Command::parse("  "), Ok(None));
+        assert_eq!(Command::parse("help"), Ok(Some(Command::Help)));
+        assert_eq!(
+            Command::parse(" get  forecast_days "),
+            Ok(Some(Command::Get(Key::ForecastDays)))
+        );
+        assert_eq!(
+            Command::parse("set wifi_ssid  My Network "),
+            Ok(Some(Command::Set(Key::WifiSsid, "My Network")))
+        );
+        assert_eq!(Command::parse("sleep 60"), Ok(Some(Command::Sleep(60))));
+    }
+
+    #[test]
+    fn refuses_malformed_commands() {
+        assert_eq!(Command::parse("reboot"), Err(CommandError::UnknownCommand));
+        assert_eq!(Command::parse("get"), Err(CommandError::MissingArgument));
+        assert_eq!(Command::parse("get colour"), Err(CommandError::UnknownKey));
+        assert_eq!(
+            Command::parse("set forecast_days"),
+            Err(CommandError::MissingArgument)
+        );
+        assert_eq!(Command::parse("sleep"), Err(CommandError::MissingArgument));
+        assert_eq!(Command::parse("sleep 0"), Err(CommandError::InvalidValue));
+        assert_eq!(Command::parse("sleep -5"), Err(CommandError::InvalidValue));
+    }
+
+    #[test]
+    fn parses_setting_values() {
+        assert_eq!(
+            Key::Location.parse_value("39.74, -104.99, America/Denver, Denver"),
+            Ok(Value::Location(Some(LocationValue {
+                latitude: "39.74",
+                longitude: "-104.99",
+                timezone: "America/Denver",
+                name: "Denver",
+            })))
+        );
+        assert_eq!(Key::Location.parse_value("none"), Ok(Value::Location(None)));
+        assert_eq!(
+            Key::Location.parse_value("39.74,-104.99"),
+            Err(CommandError::MissingArgument)
+        );
+        assert_eq!(
+            Key::Location.parse_value("91,0,UTC"),
+            Err(CommandError::InvalidValue)
+        );
+        assert_eq!(
+            Key::WindUnit.parse_value("mph"),
+            Ok(Value::Wind(SpeedUnit::MilesPerHour))
+        );
+        assert_eq!(
+            Key::PressureUnit.parse_value("bar"),
+            Err(CommandError::InvalidValue)
+        );
+        assert_eq!(Key::IpTimeoutSecs.parse_value("45"), Ok(Value::Number(45)));
+        assert_eq!(
+            Key::ForecastDays.parse_value("70000"),
+            Err(CommandError::InvalidValue)
+        );
+    }
+
+    #[test]
+    fn config_lists_settings_and_hides_the_password() {
+        let mut device = TestDevice::default();
+        let out = run(&mut device, "config");
+        assert!(out.starts_with("wifi_ssid = home\nwifi_password = ********\n"));
+        assert!(out.contains("location = none\n"));
+        assert!(out.contains("wind_unit = km/h\n"));
+        assert!(out.ends_with("alert_source = disabled\n"));
+        assert_eq!(out.lines().count(), Key::ALL.len());
+
+        device.settings.wifi_password.clear();
+        assert_eq!(run(&mut device, "get wifi_password"), "\n");
+    }
+
+    #[test]
+    fn set_saves_only_accepted_values() {
+        let mut device = TestDevice::default();
+        assert_eq!(
+            run(&mut device, "set location 39.74,-104.99,America/Denver"),
+            "ok\n"
+        );
+        assert_eq!(
+            run(&mut device, "get location"),
+            "39.74,-104.99,America/Denver,\n"
+        );
+        assert_eq!(run(&mut device, "set temperature_unit fahrenheit"), "ok\n");
+        assert_eq!(run(&mut device, "get temperature_unit"), "fahrenheit\n");
+        assert_eq!(device.saves, 2);
+
+        assert_eq!(
+            run(&mut device, "set forecast_days 300"),
+            "error: invalid value\n"
+        );
+        device.reject_save = true;
+        assert_eq!(
+            run(&mut device, "set forecast_days 0"),
+            "error: setting out of range\n"
+        );
+        assert_eq!(device.saves, 2);
+        assert_eq!(device.settings.forecast_days, 5);
+    }
+
+    #[test]
+    fn runs_device_commands() {
+        let mut device = TestDevice::default();
+        assert_eq!(run(&mut device, ""), "");
+        assert_eq!(
+            run(&mut device, "fetch"),
+            "error: network connection failed\n"
+        );
+        assert_eq!(run(&mut device, "forecast"), "Denver 21.5C\n");
+        assert_eq!(run(&mut device, "errors"), "no errors recorded\n");
+        device.errors.push((3, "HTTP request failed")).unwrap();
+        device.errors.push((4, "JSON parse error")).unwrap();
+        assert_eq!(
+            run(&mut device, "errors"),
+            "#3 HTTP request failed\n#4 JSON parse error\n"
+        );
+        assert_eq!(run(&mut device, "sleep 600"), "sleeping for 600 seconds\n");
+        assert_eq!(device.slept, Some(600));
+        assert_eq!(
+            run(&mut device, "frobnicate"),
+            "error: unknown command, see \"help\"\n"
+        );
+    }
+
+    #[test]
+    fn assembles_lines_from_bytes() {
+        let mut line = LineBuffer::default();
+        assert_eq!(line.push(b'h'), Input::Added('h'));
+        assert_eq!(line.push(b'x'), Input::Added('x'));
+        assert_eq!(line.push(0x7f), Input::Erased);
+        assert_eq!(line.push(0x1b), Input::Ignored);
+        assert_eq!(line.push(b'i'), Input::Added('i'));
+        assert_eq!(
+            line.push(b'\r'),
+            Input::Line(String::try_from("hi").unwrap())
+        );
+        // The line feed of CR LF does not finish an empty line, a second one does
+        assert_eq!(line.push(b'\n'), Input::Ignored);
+        assert_eq!(line.push(b'\n'), Input::Line(String::new()));
+        assert_eq!(line.push(0x08), Input::Ignored);
+    }
+
+    #[test]
+    fn drops_input_beyond_the_line_length() {
+        let mut line = LineBuffer::default();
+        for _ in 0..LINE_LEN {
+            assert_eq!(line.push(b'a'), Input::Added('a'));
+        }
+        assert_eq!(line.push(b'b'), Input::Ignored);
+        let Input::Line(full) = line.push(b'\n') else {
+            panic!("line not finished");
+        };
+        assert_eq!(full.len(), LINE_LEN);
+    }
+}
@@ -24,11 +24,14 @@ use epd_datafuri::prelude::*;
 use crate::{
     error::{AppError, Result},
     graphics::draw_text_xy_wh,
-    weather::{model::OpenMeteoResponse, ui::draw_weather_station_view},
+    weather::{
+        model::WeatherScreen,
+        ui::{draw_location_summary_view, draw_weather_station_view},
+    },
 };
 
 pub fn display_weather(
-    weather_data: OpenMeteoResponse,
+    screen: WeatherScreen,
     spi_device: &'static mut ExclusiveDevice<
         Spi<'static, esp_hal::Blocking>,
         Output<'static>,
@@ -40,7 +43,11 @@ pub fn display_weather(
 ) -> Result<()> {
     log::info!("Beginning weather station display");
     let mut display = Display2in9Gray2::new();
-    match draw_weather_station_view(&weather_data, &mut display) {
+    let drawn = match &screen {
+        WeatherScreen::Station(report) => draw_weather_station_view(report, &mut display),
+        WeatherScreen::Summary(report) => draw_location_summary_view(report, &mut display),
+    };
+    match drawn {
         Ok(_) => (),
         Err(e) => {
             log::error!("Failed to draw weather station view: {:?}", e);
@@ -1,3 +1,27 @@
+use core::fmt::{Display, Write as _};
+
+use heapless::{String, Vec};
+
+use crate::weather::{model::API_REASON_LEN, validate::ForecastError};
+
+/// Number of recent errors kept in the history
+pub const ERROR_HISTORY_LEN: usize = 8;
+/// Longest error message kept in the history, longer ones are cut short
+pub const ERROR_MESSAGE_LEN: usize = 64;
+const ERROR_HISTORY_MAGIC: u32 = 0x4552_524c; // "LRRE"
+
+// Recent errors, kept in RTC memory across deep sleep. Persistent RTC memory is not cleared at
+// power-on, so the history is only trusted once the magic value has been written.
+#[esp_hal::ram(unstable(rtc_fast, persistent))]
+static mut ERROR_HISTORY_VALID: u32 = 0;
+/// Number of errors recorded since the history was cleared
+#[esp_hal::ram(unstable(rtc_fast, persistent))]
+static mut ERROR_COUNT: u32 = 0;
+/// Ring of NUL-padded messages, the latest at `(ERROR_COUNT - 1) % ERROR_HISTORY_LEN`
+#[esp_hal::ram(unstable(rtc_fast, persistent))]
+static mut ERROR_MESSAGES: [[u8; ERROR_MESSAGE_LEN]; ERROR_HISTORY_LEN] =
+    [[0; ERROR_MESSAGE_LEN]; ERROR_HISTORY_LEN];
+
 /// Crate-wide result alias using the unified `AppError`.
 pub type Result<T> = core::result::Result<T, AppError>;
 
@@ -16,18 +40,45 @@ pub enum AppError {
     #[error("network connection failed")]
     ConnectionFailed,
 
+    #[error("network link failed")]
+    LinkFailed,
+
+    #[error("timed out waiting for IP address")]
+    DhcpTimeout,
+
     #[error("HTTP request failed")]
     HttpRequestFailed,
 
     #[error("socket read error")]
     SocketReadError,
 
+    #[error("HTTP response too large")]
+    ResponseTooLarge,
+
+    #[error("not enough memory for the HTTP response")]
+    OutOfMemory,
+
     #[error("API timeout error")]
     RequestTimeout,
 
     #[error("JSON parse failed")]
     JsonParseFailed,
 
+    #[error("Open-Meteo: {0}")]
+    ApiError(String<API_REASON_LEN>),
+
+    #[error("invalid forecast: {0}")]
+    InvalidForecast(#[from] ForecastError),
+
+    #[error("location not found")]
+    LocationNotFound,
+
+    #[error("flash storage access failed")]
+    StorageError,
+
+    #[error("setting out of range")]
+    InvalidSetting,
+
     #[error("an unknown error occurred")]
     Other,
 }
@@ -38,3 +89,72 @@ impl From<serde_json_core::de::Error> for AppError {
         AppError::JsonParseFailed
     }
 }
+
+/// Entry of the error history
+#[derive(Debug, Clone)]
+pub struct RecordedError {
+    /// Position among all errors recorded since the history was cleared, starting at 1
+    pub number: u32,
+    pub message: String<ERROR_MESSAGE_LEN>,
+}
+
+/// Add `error` to the history of recent errors, dropping the oldest when it is full.
+pub fn record_error(error: impl Display) {
+    let mut message: String<ERROR_MESSAGE_LEN> = String::new();
+    let _ = write!(Truncating(&mut message), "{}", error);
+
+    // SAFETY: only accessed from the main executor, before and after deep sleep
+    unsafe {
+        if (&raw const ERROR_HISTORY_VALID).read_volatile() != ERROR_HISTORY_MAGIC {
+            (&raw mut ERROR_COUNT).write_volatile(0);
+            (&raw mut ERROR_HISTORY_VALID).write_volatile(ERROR_HISTORY_MAGIC);
+        }
+        let count = (&raw const ERROR_COUNT).read_volatile();
+        let mut entry = [0u8; ERROR_MESSAGE_LEN];
+        entry[..message.len()].copy_from_slice(message.as_bytes());
+        (&raw mut ERROR_MESSAGES[count as usize % ERROR_HISTORY_LEN]).write_volatile(entry);
+        (&raw mut ERROR_COUNT).write_volatile(count.wrapping_add(1));
+    }
+}
+
+/// Recent errors, oldest first
+pub fn error_history() -> Vec<RecordedError, ERROR_HISTORY_LEN> {
+    let mut history = Vec::new();
+    // SAFETY: only accessed from the main executor, before and after deep sleep
+    unsafe {
+        if (&raw const ERROR_HISTORY_VALID).read_volatile() != ERROR_HISTORY_MAGIC {
+            return history;
+        }
+        let count = (&raw const ERROR_COUNT).read_volatile();
+        let first = count.saturating_sub(ERROR_HISTORY_LEN as u32);
+        for number in first..count {
+            let entry =
+                (&raw const ERROR_MESSAGES[number as usize % ERROR_HISTORY_LEN]).read_volatile();
+            let len = entry
+                .iter()
+                .position(|&b| b == 0)
+                .unwrap_or(ERROR_MESSAGE_LEN);
+            let message = core::str::from_utf8(&entry[..len])
+                .ok()
+                .and_then(|text| String::try_from(text).ok())
+                .unwrap_or_default();
+            let _ = history.push(RecordedError {
+                number: number + 1,
+                message,
+            });
+        }
+    }
+    history
+}
+
+/// Writes as much of the formatted text as fits into the string, dropping the rest
+struct Truncating<'a, const N: usize>(&'a mut String<N>);
+
+impl<const N: usize> core::fmt::Write for Truncating<'_, N> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for c in s.chars() {
+            self.0.push(c).map_err(|_| core::fmt::Error)?;
+        }
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@ use embedded_graphics::{
     mono_font::MonoTextStyle,
     pixelcolor::{BinaryColor, Gray2},
     prelude::*,
-    primitives::Rectangle,
+    primitives::{PrimitiveStyle, Rectangle},
 };
 use embedded_text::{
     TextBox,
@@ -23,6 +23,14 @@ pub static CHARACTER_STYLE: Lazy<MonoTextStyle<Gray2>> = Lazy::new(|| {
     )
 });
 
+// inverted text style for banners on a black background
+static BANNER_CHARACTER_STYLE: Lazy<MonoTextStyle<Gray2>> = Lazy::new(|| {
+    MonoTextStyle::new(
+        &embedded_graphics::mono_font::ascii::FONT_6X10,
+        Gray2::WHITE,
+    )
+});
+
 /// Draw `text` inside a rectangle at `(x,y)` with width `w` and height `h` on `buffer` using the module text style.
 ///
 /// Returns `Ok(())` on success or `AppError::GraphicsError` on failure.
@@ -58,6 +66,38 @@ where
     Ok(())
 }
 
+/// Fill a rectangle at `top_left` with `size` in black and draw `text` centered on it in white.
+///
+/// Returns `Ok(())` on success or `AppError::GraphicsError` on failure.
+pub(crate) fn draw_banner<D>(text: &str, top_left: Point, size: Size, buffer: &mut D) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    let bounds = Rectangle::new(top_left, size);
+    bounds
+        .into_styled(PrimitiveStyle::with_fill(Gray2::BLACK))
+        .draw(buffer)
+        .map_err(|e| {
+            log::error!("Failed to draw banner to display buffer: {:?}", e);
+            AppError::GraphicsError
+        })?;
+
+    let textbox_style = TextBoxStyleBuilder::new()
+        .height_mode(HeightMode::FitToText)
+        .alignment(HorizontalAlignment::Center)
+        .build();
+    let text_bounds = Rectangle::new(top_left + Point::new(0, 2), Size::new(size.width, 0));
+    let text_box =
+        TextBox::with_textbox_style(text, text_bounds, *BANNER_CHARACTER_STYLE, textbox_style);
+    text_box.draw(buffer).map_err(|e| {
+        log::error!("Failed to draw banner text to display buffer: {:?}", e);
+        AppError::GraphicsError
+    })?;
+
+    Ok(())
+}
+
 /// Draw a Gray2 `image` at `position` onto `buffer`.
 ///
 /// Returns `Ok(())` on success or `AppError::GraphicsError` on failure.
@@ -0,0 +1,339 @@
+pub mod qualifier;
+pub mod resolved;
+
+use core::fmt::Write as _;
+
+use heapless::{String, Vec};
+use serde::{Deserialize, Serialize};
+
+pub use self::resolved::Location;
+use self::{
+    qualifier::{Place, find_qualified},
+    resolved::NAME_LEN,
+};
+use crate::{
+    config::{IP_GEOLOCATION_HOST, LOCATIONS},
+    error::{AppError, Result},
+    network::geolocation::{IpGeolocationResponse, fetch_ip_geolocation},
+    settings,
+    storage::{self, Slot},
+    weather::{api::fetch_geocoding, model::GeocodingResult},
+};
+
+/// Number of resolved place name and auto locations kept in the flash cache
+const MAX_CACHED_LOCATIONS: usize = 8;
+
+// The active location index is kept in a single byte of RTC memory
+const _: () = assert!(!LOCATIONS.is_empty() && LOCATIONS.len() <= u8::MAX as usize + 1);
+
+/// Index into `LOCATIONS` of the active location, kept in RTC memory across deep sleep
+#[esp_hal::ram(unstable(rtc_fast, persistent))]
+static mut ACTIVE_LOCATION: u8 = 0;
+
+/// Compiled-in decimal coordinates and IANA timezone
+#[derive(Debug, Clone, Copy)]
+pub struct Coordinates {
+    pub latitude: &'static str,
+    pub longitude: &'static str,
+    pub timezone: &'static str,
+}
+
+/// Where a saved location's coordinates come from
+#[derive(Debug, Clone, Copy)]
+pub enum LocationSource {
+    /// Fixed coordinates and timezone
+    Coordinates(Coordinates),
+    /// A place name ("Name" or "Name, Qualifier") resolved with the Open-Meteo geocoding API
+    PlaceName(&'static str),
+    /// The public IP address located with the `IP_GEOLOCATION_*` endpoint, falling back to the
+    /// given coordinates when the lookup fails
+    Auto(Coordinates),
+}
+
+/// Named entry of the `LOCATIONS` list
+#[derive(Debug, Clone, Copy)]
+pub struct SavedLocation {
+    /// Label shown in the header; when empty the resolved place name or coordinates are shown
+    pub name: &'static str,
+    pub source: LocationSource,
+}
+
+/// Resolved location cached in flash together with the lookup that produced it
+#[derive(Serialize, Deserialize, Debug)]
+struct CachedLocation {
+    /// Place name, or the IP geolocation host for auto locations
+    key: String<NAME_LEN>,
+    location: Location,
+}
+
+/// Saved location the forecast is currently fetched for
+pub fn active_location() -> &'static SavedLocation {
+    &LOCATIONS[active_location_index()]
+}
+
+/// Make the next saved location active, wrapping around at the end of the list
+pub fn select_next_location() {
+    set_active_location_index((active_location_index() + 1) % LOCATIONS.len());
+}
+
+/// Make the previous saved location active, wrapping around at the start of the list
+pub fn select_previous_location() {
+    let index = active_location_index();
+    set_active_location_index((index + LOCATIONS.len() - 1) % LOCATIONS.len());
+}
+
+/// Index into `LOCATIONS` of the active saved location
+pub fn active_location_index() -> usize {
+    // SAFETY: only accessed from the main executor, before and after deep sleep
+    let index = unsafe { (&raw const ACTIVE_LOCATION).read_volatile() } as usize;
+    // A shorter list in new firmware can leave a stale index behind
+    if index < LOCATIONS.len() { index } else { 0 }
+}
+
+fn set_active_location_index(index: usize) {
+    // SAFETY: only accessed from the main executor, before and after deep sleep
+    unsafe { (&raw mut ACTIVE_LOCATION).write_volatile(index as u8) };
+    log::info!("Active location: {} ({})", index, LOCATIONS[index].name);
+}
+
+impl Location {
+    /// Location built from compiled-in coordinates and timezone
+    fn from_coordinates(coordinates: &Coordinates) -> Self {
+        Location {
+            name: String::new(),
+            latitude: String::try_from(coordinates.latitude).unwrap_or_default(),
+            longitude: String::try_from(coordinates.longitude).unwrap_or_default(),
+            timezone: String::try_from(coordinates.timezone).unwrap_or_default(),
+        }
+    }
+
+    fn from_geocoding(result: &GeocodingResult) -> Self {
+        let mut location = Location {
+            name: String::new(),
+            latitude: String::new(),
+            longitude: String::new(),
+            timezone: String::new(),
+        };
+
+        let _ = location.name.push_str(&result.name);
+        // Append the region only when it fits in full
+        if let Some(region) = result.admin1.as_deref().or(result.country.as_deref())
+            && result.name.len() + 2 + region.len() <= NAME_LEN
+        {
+            let _ = write!(location.name, ", {}", region);
+        }
+        let _ = write!(location.latitude, "{:.4}", result.latitude);
+        let _ = write!(location.longitude, "{:.4}", result.longitude);
+        // Open-Meteo picks the timezone from the coordinates with "auto"
+        let timezone = result.timezone.as_deref().unwrap_or("auto");
+        let _ = location.timezone.push_str(timezone);
+        location
+    }
+
+    fn from_ip_geolocation(response: &IpGeolocationResponse) -> Self {
+        let mut location = Location {
+            name: String::new(),
+            latitude: String::new(),
+            longitude: String::new(),
+            timezone: String::new(),
+        };
+
+        if let Some(city) = response.city.as_deref() {
+            let _ = location.name.push_str(city);
+            // Append the region only when it fits in full
+            if let Some(region) = response.region.as_deref()
+                && city.len() + 2 + region.len() <= NAME_LEN
+            {
+                let _ = write!(location.name, ", {}", region);
+            }
+        }
+        let _ = write!(
+            location.latitude,
+            "{:.4}",
+            response.latitude.unwrap_or_default()
+        );
+        let _ = write!(
+            location.longitude,
+            "{:.4}",
+            response.longitude.unwrap_or_default()
+        );
+        // Open-Meteo picks the timezone from the coordinates with "auto"
+        let timezone = response.timezone.as_deref().unwrap_or("auto");
+        let _ = location.timezone.push_str(timezone);
+        location
+    }
+}
+
+/// Resolve the active saved location, labelled with its configured name when it has one.
+///
+/// A location set in the runtime settings takes the place of the saved locations.
+pub async fn resolve_location(stack: embassy_net::Stack<'static>) -> Result<Location> {
+    if let Some(location) = settings::get().location {
+        return Ok(location);
+    }
+    resolve_saved(stack, active_location()).await
+}
+
+/// Resolve the active location without the network, from the runtime settings, compiled-in
+/// coordinates or the location cache.
+///
+/// Returns `None` for a place name or auto location that has not been looked up yet.
+pub async fn resolve_location_offline() -> Option<Location> {
+    if let Some(location) = settings::get().location {
+        return Some(location);
+    }
+    let saved = active_location();
+    let location = match saved.source {
+        LocationSource::Coordinates(coordinates) => Location::from_coordinates(&coordinates),
+        LocationSource::PlaceName(name) => cached_location(name).await?,
+        // An auto location that was never looked up is fetched at its configured coordinates
+        LocationSource::Auto(coordinates) => cached_location(IP_GEOLOCATION_HOST)
+            .await
+            .unwrap_or_else(|| Location::from_coordinates(&coordinates)),
+    };
+    Some(with_saved_name(location, saved))
+}
+
+/// Resolve the first `N` saved locations in list order.
+pub async fn resolve_locations<const N: usize>(
+    stack: embassy_net::Stack<'static>,
+) -> Result<Vec<Location, N>> {
+    let mut locations = Vec::new();
+    for saved in LOCATIONS.iter().take(N) {
+        let _ = locations.push(resolve_saved(stack, saved).await?);
+    }
+    Ok(locations)
+}
+
+async fn resolve_saved(
+    stack: embassy_net::Stack<'static>,
+    saved: &SavedLocation,
+) -> Result<Location> {
+    let location = resolve_source(stack, saved.source).await?;
+    Ok(with_saved_name(location, saved))
+}
+
+/// Label `location` with the configured name of `saved`, when it has one
+fn with_saved_name(mut location: Location, saved: &SavedLocation) -> Location {
+    if !saved.name.is_empty() {
+        location.name.clear();
+        let _ = location.name.push_str(saved.name);
+    }
+    location
+}
+
+/// The location cached for the place name or geolocation host `key`
+async fn cached_location(key: &str) -> Option<Location> {
+    let cache = storage::load::<Vec<CachedLocation, MAX_CACHED_LOCATIONS>>(Slot::Location).await?;
+    cache
+        .into_iter()
+        .find(|cached| cached.key == key)
+        .map(|cached| cached.location)
+}
+
+/// Resolve a location source to coordinates.
+///
+/// Place names and auto locations are looked up once and cached in flash for later wakes.
+/// An auto location that cannot be determined falls back to its configured coordinates.
+async fn resolve_source(
+    stack: embassy_net::Stack<'static>,
+    source: LocationSource,
+) -> Result<Location> {
+    let key = match source {
+        LocationSource::Coordinates(coordinates) => {
+            return Ok(Location::from_coordinates(&coordinates));
+        }
+        LocationSource::PlaceName(name) => name,
+        LocationSource::Auto(_) => IP_GEOLOCATION_HOST,
+    };
+
+    let mut cache = storage::load::<Vec<CachedLocation, MAX_CACHED_LOCATIONS>>(Slot::Location)
+        .await
+        .unwrap_or_default();
+    if let Some(cached) = cache.iter().find(|cached| cached.key == key) {
+        log::info!("Using cached location: {}", cached.location.name);
+        return Ok(cached.location.clone());
+    }
+
+    let resolved = match source {
+        LocationSource::PlaceName(name) => geocode(stack, name).await,
+        _ => fetch_ip_geolocation(stack)
+            .await
+            .map(|response| Location::from_ip_geolocation(&response)),
+    };
+    let location = match resolved {
+        Ok(location) => location,
+        Err(e) => {
+            if let LocationSource::Auto(coordinates) = source {
+                // Not cached, so the lookup is retried on the next wake
+                log::warn!(
+                    "IP geolocation failed, using configured coordinates: {:?}",
+                    e
+                );
+                return Ok(Location::from_coordinates(&coordinates));
+            }
+            return Err(e);
+        }
+    };
+    log::info!(
+        "Resolved \"{}\" to {} ({}, {})",
+        key,
+        location.name,
+        location.latitude,
+        location.longitude
+    );
+
+    if let Ok(key) = String::try_from(key) {
+        // Evict the oldest entry to make room
+        if cache.is_full() {
+            cache.remove(0);
+        }
+        let _ = cache.push(CachedLocation {
+            key,
+            location: location.clone(),
+        });
+        if let Err(e) = storage::save(Slot::Location, &cache).await {
+            log::warn!("Failed to cache location: {:?}", e);
+        }
+    }
+
+    Ok(location)
+}
+
+/// Look up `query` ("Name" or "Name, Qualifier") with the geocoding API.
+///
+/// The qualifier selects between places sharing a name by matching the region (or US state
+/// abbreviation), country or country code; without a match the best-ranked result is used.
+async fn geocode(stack: embassy_net::Stack<'static>, query: &str) -> Result<Location> {
+    let (name, qualifier) = match query.split_once(',') {
+        Some((name, qualifier)) => (name.trim(), Some(qualifier.trim())),
+        None => (query.trim(), None),
+    };
+
+    let response = fetch_geocoding(stack, name).await?;
+    let results = response.results.unwrap_or_default();
+
+    let result = qualifier
+        .and_then(|qualifier| find_qualified(&results, qualifier))
+        .or(results.first())
+        .ok_or_else(|| {
+            log::error!("No geocoding results for \"{}\"", query);
+            AppError::LocationNotFound
+        })?;
+
+    Ok(Location::from_geocoding(result))
+}
+
+impl Place for GeocodingResult {
+    fn admin1(&self) -> Option<&str> {
+        self.admin1.as_deref()
+    }
+
+    fn country(&self) -> Option<&str> {
+        self.country.as_deref()
+    }
+
+    fn country_code(&self) -> Option<&str> {
+        self.country_code.as_deref()
+    }
+}
@@ -0,0 +1,179 @@
+//! Choosing between geocoding results by the qualifier of a "Name, Qualifier" place name
+
+/// Region and country fields of a geocoding result
+pub trait Place {
+    fn admin1(&self) -> Option<&str>;
+    fn country(&self) -> Option<&str>;
+    fn country_code(&self) -> Option<&str>;
+}
+
+/// US state abbreviations and the `admin1` names geocoding returns for them
+const US_STATES: [(&str, &str); 51] = [
+    ("AL", "Alabama"),
+    ("AK", "Alaska"),
+    ("AZ", "Arizona"),
+    ("AR", "Arkansas"),
+    ("CA", "California"),
+    ("CO", "Colorado"),
+    ("CT", "Connecticut"),
+    ("DE", "Delaware"),
+    ("DC", "District of Columbia"),
+    ("FL", "Florida"),
+    ("GA", "Georgia"),
+    ("HI", "Hawaii"),
+    ("ID", "Idaho"),
+    ("IL", "Illinois"),
+    ("IN", "Indiana"),
+    ("IA", "Iowa"),
+    ("KS", "Kansas"),
+    ("KY", "Kentucky"),
+    ("LA", "Louisiana"),
+    ("ME", "Maine"),
+    ("MD", "Maryland"),
+    ("MA", "Massachusetts"),
+    ("MI", "Michigan"),
+    ("MN", "Minnesota"),
+    ("MS", "Mississippi"),
+    ("MO", "Missouri"),
+    ("MT", "Montana"),
+    ("NE", "Nebraska"),
+    ("NV", "Nevada"),
+    ("NH", "New Hampshire"),
+    ("NJ", "New Jersey"),
+    ("NM", "New Mexico"),
+    ("NY", "New York"),
+    ("NC", "North Carolina"),
+    ("ND", "North Dakota"),
+    ("OH", "Ohio"),
+    ("OK", "Oklahoma"),
+    ("OR", "Oregon"),
+    ("PA", "Pennsylvania"),
+    ("RI", "Rhode Island"),
+    ("SC", "South Carolina"),
+    ("SD", "South Dakota"),
+    ("TN", "Tennessee"),
+    ("TX", "Texas"),
+    ("UT", "Utah"),
+    ("VT", "Vermont"),
+    ("VA", "Virginia"),
+    ("WA", "Washington"),
+    ("WV", "West Virginia"),
+    ("WI", "Wisconsin"),
+    ("WY", "Wyoming"),
+];
+
+/// First place matching `qualifier`, trying the region of every place before its country
+/// name and country code.
+///
+/// A two-letter qualifier is also looked up as a US state abbreviation, so "Boulder, CO" finds
+/// Colorado before it finds Colombia.
+pub fn find_qualified<'a, P: Place>(places: &'a [P], qualifier: &str) -> Option<&'a P> {
+    let eq = |field: Option<&str>| field.is_some_and(|field| field.eq_ignore_ascii_case(qualifier));
+    let us_state = US_STATES
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(qualifier))
+        .map(|&(_, name)| name);
+
+    places
+        .iter()
+        .find(|place| {
+            eq(place.admin1())
+                || us_state.is_some_and(|state| {
+                    place.country_code() == Some("US") && place.admin1() == Some(state)
+                })
+        })
+        .or_else(|| places.iter().find(|place| eq(place.country())))
+        .or_else(|| places.iter().find(|place| eq(place.country_code())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestPlace {
+        admin1: &'static str,
+        country: &'static str,
+        country_code: &'static str,
+    }
+
+    impl Place for TestPlace {
+        fn admin1(&self) -> Option<&str> {
+            Some(self.admin1)
+        }
+        fn country(&self) -> Option<&str> {
+            Some(self.country)
+        }
+        fn country_code(&self) -> Option<&str> {
+            Some(self.country_code)
+        }
+    }
+
+    // Ranked results for "Boulder", with a place in Colombia ahead of the one in Colorado
+    const BOULDER: [TestPlace; 3] = [
+        TestPlace {
+            admin1: "Antioquia",
+            country: "Colombia",
+            country_code: "CO",
+        },
+        TestPlace {
+            admin1: "Colorado",
+            country: "United States",
+            country_code: "US",
+        },
+        TestPlace {
+            admin1: "Western Australia",
+            country: "Australia",
+            country_code: "AU",
+        },
+    ];
+
+    #[test]
+    fn state_abbreviation_wins_over_country_code() {
+        let place = find_qualified(&BOULDER, "CO").unwrap();
+        assert_eq!(place.admin1, "Colorado");
+        let place = find_qualified(&BOULDER, "co").unwrap();
+        assert_eq!(place.admin1, "Colorado");
+    }
+
+    #[test]
+    fn matches_region_then_country_then_country_code() {
+        assert_eq!(
+            find_qualified(&BOULDER, "western australia")
+                .unwrap()
+                .admin1,
+            "Western Australia"
+        );
+        assert_eq!(
+            find_qualified(&BOULDER, "Colombia").unwrap().admin1,
+            "Antioquia"
+        );
+        assert_eq!(
+            find_qualified(&BOULDER, "AU").unwrap().admin1,
+            "Western Australia"
+        );
+    }
+
+    #[test]
+    fn unknown_qualifier_has_no_match() {
+        assert!(find_qualified(&BOULDER, "NY").is_none());
+        assert!(find_qualified(&BOULDER, "Nowhere").is_none());
+    }
+
+    #[test]
+    fn state_abbreviation_needs_a_us_place() {
+        // "CA" is California for a US place and Canada otherwise
+        let places = [
+            TestPlace {
+                admin1: "Ontario",
+                country: "Canada",
+                country_code: "CA",
+            },
+            TestPlace {
+                admin1: "California",
+                country: "Mexico",
+                country_code: "MX",
+            },
+        ];
+        assert_eq!(find_qualified(&places, "CA").unwrap().admin1, "Ontario");
+    }
+}
@@ -0,0 +1,26 @@
+//! Location a forecast is fetched for, as resolved from a saved location or set at runtime
+
+use heapless::String;
+use serde::{Deserialize, Serialize};
+
+// Heuristic string capacities
+pub const NAME_LEN: usize = 48;
+const COORD_LEN: usize = 16;
+const TZ_LEN: usize = 32;
+
+/// Forecast location passed to the Open-Meteo requests
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Location {
+    /// Place name shown in the header, empty when only coordinates are known
+    pub name: String<NAME_LEN>,
+    pub latitude: String<COORD_LEN>,
+    pub longitude: String<COORD_LEN>,
+    pub timezone: String<TZ_LEN>,
+}
+
+impl Location {
+    /// Whether `other` has the same coordinates, whatever its name
+    pub fn same_place(&self, other: &Location) -> bool {
+        self.latitude == other.latitude && self.longitude == other.longitude
+    }
+}
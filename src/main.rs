@@ -4,12 +4,20 @@
 #[macro_use]
 extern crate alloc;
 
+mod comfort;
 mod config;
+mod console;
 mod display;
 mod error;
 mod graphics;
+mod location;
+mod moon;
 mod network;
+mod settings;
+mod storage;
+mod sun;
 mod time;
+mod units;
 mod weather;
 
 // Use https://docs.rs/static_cell/2.1.1/static_cell/macro.make_static.html
@@ -25,6 +33,8 @@ macro_rules! mk_static {
 
 mod tasks;
 
+use core::sync::atomic::AtomicBool;
+
 use embassy_executor::Spawner;
 use embassy_time::{Duration, Timer};
 use esp_backtrace as _;
@@ -39,8 +49,7 @@ use embassy_sync::{
     channel::Channel,
     signal::Signal,
 };
-use crate::config::SLEEP_ON_ERROR_SECS;
-use crate::weather::model::OpenMeteoResponse;
+use crate::weather::model::WeatherScreen;
 
 use crate::tasks::sleep::SleepReason;
 
@@ -48,15 +57,19 @@ use crate::tasks::sleep::SleepReason;
 pub(crate) static SLEEP_REQUEST: Signal<CriticalSectionRawMutex, (u64, SleepReason)> =
     Signal::new();
 
-/// Signal used to notify the weather task to begin fetch
-pub(crate) static NETWORK_READY: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+/// Set while a serial console session is open, which holds off deep sleep
+pub(crate) static CONSOLE_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Signal used to notify the weather task to begin fetch, or why the network is unavailable
+pub(crate) static NETWORK_READY: Signal<CriticalSectionRawMutex, error::Result<()>> =
+    Signal::new();
 
 /// Signal used to notify display task of network/fetch errors
 pub(crate) static NETWORK_ERROR: Signal<CriticalSectionRawMutex, heapless::String<128>> =
     Signal::new();
 
 /// Channel used to deliver weather data to the display task
-pub(crate) static DATA_CHANNEL: Channel<CriticalSectionRawMutex, OpenMeteoResponse, 1> =
+pub(crate) static DATA_CHANNEL: Channel<CriticalSectionRawMutex, WeatherScreen, 1> =
     Channel::new();
 
 esp_bootloader_esp_idf::esp_app_desc!();
@@ -64,8 +77,8 @@ esp_bootloader_esp_idf::esp_app_desc!();
 #[esp_rtos::main]
 async fn main(spawner: Spawner) -> ! {
     init_logger_from_env();
-    // 64KB heap for network stack, JSON parsing, and HTTP buffers
-    esp_alloc::heap_allocator!(#[esp_hal::ram(reclaimed)] size: 64000);
+    // Heap for network stack, JSON parsing, and HTTP buffers
+    esp_alloc::heap_allocator!(#[esp_hal::ram(reclaimed)] size: config::HEAP_SIZE);
 
     log::info!("Initializing peripherals");
     let peripherals = esp_hal::init(esp_hal::Config::default());
@@ -74,8 +87,17 @@ async fn main(spawner: Spawner) -> ! {
     let sw_interrupt = SoftwareInterruptControl::new(peripherals.SW_INTERRUPT);
     esp_rtos::start(timg0.timer0, sw_interrupt.software_interrupt0);
 
+    storage::init(peripherals.FLASH);
+    settings::load().await;
+    tasks::sleep::handle_wakeup();
+
     spawner.spawn(
-        tasks::sleep::deep_sleep_task(peripherals.LPWR).expect("Failed to spawn deep sleep task"),
+        tasks::sleep::deep_sleep_task(tasks::sleep::SleepResources {
+            lpwr: peripherals.LPWR,
+            next_button: peripherals.GPIO11,
+            previous_button: peripherals.GPIO15,
+        })
+        .expect("Failed to spawn deep sleep task"),
     );
 
     spawner.spawn(
@@ -99,7 +121,8 @@ async fn main(spawner: Spawner) -> ! {
             Ok(pair) => pair,
             Err(e) => {
                 log::error!("Failed to initialize radio: {:?}", e);
-                SLEEP_REQUEST.signal((SLEEP_ON_ERROR_SECS, SleepReason::NetworkError));
+                // The display task sleeps once it has shown the saved forecast or the error
+                tasks::weather::show_fetch_failure(e).await;
                 loop {
                     Timer::after(Duration::from_secs(60)).await;
                 }
@@ -118,6 +141,17 @@ async fn main(spawner: Spawner) -> ! {
         tasks::weather::weather_fetcher_task(stack).expect("Failed to spawn weather_fetcher_task"),
     );
 
+    spawner.spawn(
+        tasks::console::console_task(
+            tasks::console::ConsoleResources {
+                uart: peripherals.UART0,
+                rx: peripherals.GPIO44,
+            },
+            stack,
+        )
+        .expect("Failed to spawn console_task"),
+    );
+
     loop {
         Timer::after_secs(1).await;
     }
@@ -0,0 +1,330 @@
+use core::f32::consts::PI;
+
+use libm::{atan2f, cosf, sinf, sqrtf};
+
+const SECS_PER_DAY: i64 = 86_400;
+const SYNODIC_MONTH_DAYS: f32 = 29.530_588;
+/// 2000-01-01T12:00:00Z, the J2000.0 epoch
+const J2000_UNIX: i64 = 946_728_000;
+const ARCSEC_PER_RADIAN: f32 = 206_264.81;
+/// Obliquity of the ecliptic at J2000.0 (23.4393 degrees)
+const OBLIQUITY: f32 = 0.409_093;
+/// Altitude of the moon's centre at rise and set, after parallax and refraction (degrees)
+const MOONRISE_ALTITUDE: f32 = 0.125;
+
+/// Named phase of the moon, one eighth of the synodic month each
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoonPhase {
+    New,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    Full,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+impl MoonPhase {
+    /// Phase centred on the given elongation in degrees
+    fn from_elongation(elongation: f32) -> Self {
+        match ((elongation + 22.5) / 45.0) as u32 % 8 {
+            0 => MoonPhase::New,
+            1 => MoonPhase::WaxingCrescent,
+            2 => MoonPhase::FirstQuarter,
+            3 => MoonPhase::WaxingGibbous,
+            4 => MoonPhase::Full,
+            5 => MoonPhase::WaningGibbous,
+            6 => MoonPhase::LastQuarter,
+            _ => MoonPhase::WaningCrescent,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MoonPhase::New => "New Moon",
+            MoonPhase::WaxingCrescent => "Waxing Crescent",
+            MoonPhase::FirstQuarter => "First Quarter",
+            MoonPhase::WaxingGibbous => "Waxing Gibbous",
+            MoonPhase::Full => "Full Moon",
+            MoonPhase::WaningGibbous => "Waning Gibbous",
+            MoonPhase::LastQuarter => "Last Quarter",
+            MoonPhase::WaningCrescent => "Waning Crescent",
+        }
+    }
+}
+
+/// Moon phase and the moonrise and moonset of one local day
+#[derive(Debug, Clone, Copy)]
+pub struct MoonInfo {
+    pub phase: MoonPhase,
+    /// Ecliptic longitude of the moon east of the sun in degrees: 0 new, 180 full
+    pub elongation: f32,
+    /// Illuminated fraction of the disk, from 0.0 to 1.0
+    pub illumination: f32,
+    /// Unix time of moonrise, `None` if the moon does not rise that day
+    pub moonrise: Option<i64>,
+    /// Unix time of moonset, `None` if the moon does not set that day
+    pub moonset: Option<i64>,
+}
+
+impl MoonInfo {
+    /// Days since the last new moon
+    pub fn age_days(&self) -> f32 {
+        self.elongation / 360.0 * SYNODIC_MONTH_DAYS
+    }
+}
+
+/// Compute the moon phase at unix time `ts` and the moonrise and moonset of the local day
+/// containing it, for an observer at `latitude` and `longitude` in degrees.
+///
+/// Uses low precision series good to a few minutes of time, which is plenty for display.
+pub fn moon_info(ts: i64, utc_offset_seconds: i32, latitude: f32, longitude: f32) -> MoonInfo {
+    let t = centuries_since_j2000(ts);
+    let (moon_longitude, _) = moon_ecliptic(t);
+    let elongation = normalize_degrees((moon_longitude - sun_longitude(t)).to_degrees());
+    let illumination = (1.0 - cosf(elongation.to_radians())) / 2.0;
+
+    let offset = utc_offset_seconds as i64;
+    let local_midnight = (ts + offset).div_euclid(SECS_PER_DAY) * SECS_PER_DAY - offset;
+    let (moonrise, moonset) = moonrise_moonset(local_midnight, latitude, longitude);
+
+    MoonInfo {
+        phase: MoonPhase::from_elongation(elongation),
+        elongation,
+        illumination,
+        moonrise,
+        moonset,
+    }
+}
+
+/// Find the moonrise and moonset in the 24 hours from `day_start`.
+///
+/// Samples the moon's altitude every hour and fits a parabola through each three samples
+/// (Montenbruck and Pfleger, "Astronomy on the Personal Computer").
+fn moonrise_moonset(day_start: i64, latitude: f32, longitude: f32) -> (Option<i64>, Option<i64>) {
+    let sin_h0 = sinf(MOONRISE_ALTITUDE.to_radians());
+    let altitude_at =
+        |hour: i64| sin_altitude(day_start + hour * 3600, latitude, longitude) - sin_h0;
+
+    let mut moonrise = None;
+    let mut moonset = None;
+    let mut y_minus = altitude_at(0);
+    for hour in (1..24).step_by(2) {
+        let y0 = altitude_at(hour);
+        let y_plus = altitude_at(hour + 1);
+
+        let a = 0.5 * (y_plus + y_minus) - y0;
+        let b = 0.5 * (y_plus - y_minus);
+        let x_extreme = -b / (2.0 * a);
+        let y_extreme = (a * x_extreme + b) * x_extreme + y0;
+        let discriminant = b * b - 4.0 * a * y0;
+
+        if discriminant >= 0.0 {
+            let dx = 0.5 * sqrtf(discriminant) / a.abs();
+            let roots = [x_extreme - dx, x_extreme + dx];
+            let in_range = |x: f32| (-1.0..=1.0).contains(&x);
+            let to_ts = |x: f32| day_start + ((hour as f32 + x) * 3600.0) as i64;
+            match (in_range(roots[0]), in_range(roots[1])) {
+                (true, true) => {
+                    let (rise, set) = if y_extreme < 0.0 {
+                        (roots[1], roots[0])
+                    } else {
+                        (roots[0], roots[1])
+                    };
+                    moonrise = moonrise.or(Some(to_ts(rise)));
+                    moonset = moonset.or(Some(to_ts(set)));
+                }
+                (first, second) if first != second => {
+                    let root = if first { roots[0] } else { roots[1] };
+                    if y_minus < 0.0 {
+                        moonrise = moonrise.or(Some(to_ts(root)));
+                    } else {
+                        moonset = moonset.or(Some(to_ts(root)));
+                    }
+                }
+                _ => (),
+            }
+        }
+        y_minus = y_plus;
+    }
+    (moonrise, moonset)
+}
+
+/// Sine of the moon's geometric altitude at unix time `ts`
+fn sin_altitude(ts: i64, latitude: f32, longitude: f32) -> f32 {
+    let t = centuries_since_j2000(ts);
+    let (moon_longitude, moon_latitude) = moon_ecliptic(t);
+    let (right_ascension, declination) = ecliptic_to_equatorial(moon_longitude, moon_latitude);
+
+    let hour_angle = (sidereal_time_degrees(ts) + longitude).to_radians() - right_ascension;
+    let latitude = latitude.to_radians();
+    sinf(latitude) * sinf(declination) + cosf(latitude) * cosf(declination) * cosf(hour_angle)
+}
+
+/// Low precision geocentric ecliptic longitude and latitude of the moon in radians
+fn moon_ecliptic(t: f64) -> (f32, f32) {
+    // Mean elements in revolutions, reduced before converting to f32
+    let mean_longitude = frac(0.606_433 + 1_336.855_225 * t) as f32;
+    let l = 2.0 * PI * frac(0.374_897 + 1_325.552_410 * t) as f32;
+    let ls = 2.0 * PI * frac(0.993_133 + 99.997_361 * t) as f32;
+    let d = 2.0 * PI * frac(0.827_361 + 1_236.853_086 * t) as f32;
+    let f = 2.0 * PI * frac(0.259_086 + 1_342.227_825 * t) as f32;
+
+    // Periodic perturbations of the longitude in arcseconds
+    let dl = 22640.0 * sinf(l) - 4586.0 * sinf(l - 2.0 * d)
+        + 2370.0 * sinf(2.0 * d)
+        + 769.0 * sinf(2.0 * l)
+        - 668.0 * sinf(ls)
+        - 412.0 * sinf(2.0 * f)
+        - 212.0 * sinf(2.0 * l - 2.0 * d)
+        - 206.0 * sinf(l + ls - 2.0 * d)
+        + 192.0 * sinf(l + 2.0 * d)
+        - 165.0 * sinf(ls - 2.0 * d)
+        - 125.0 * sinf(d)
+        - 110.0 * sinf(l + ls)
+        + 148.0 * sinf(l - ls)
+        - 55.0 * sinf(2.0 * f - 2.0 * d);
+
+    let s = f + (dl + 412.0 * sinf(2.0 * f) + 541.0 * sinf(ls)) / ARCSEC_PER_RADIAN;
+    let h = f - 2.0 * d;
+    let n = -526.0 * sinf(h) + 44.0 * sinf(l + h) - 31.0 * sinf(h - l) - 23.0 * sinf(ls + h)
+        + 11.0 * sinf(h - ls)
+        - 25.0 * sinf(f - 2.0 * l)
+        + 21.0 * sinf(f - l);
+
+    let longitude = 2.0 * PI * frac(mean_longitude as f64 + dl as f64 / 1_296_000.0) as f32;
+    let latitude = (18520.0 * sinf(s) + n) / ARCSEC_PER_RADIAN;
+    (longitude, latitude)
+}
+
+/// Low precision ecliptic longitude of the sun in radians
+fn sun_longitude(t: f64) -> f32 {
+    let days = t * 36525.0;
+    let mean_longitude = normalize_degrees_f64(280.460 + 0.985_647_4 * days) as f32;
+    let mean_anomaly = normalize_degrees_f64(357.528 + 0.985_600_3 * days) as f32;
+    let g = mean_anomaly.to_radians();
+    (mean_longitude + 1.915 * sinf(g) + 0.020 * sinf(2.0 * g)).to_radians()
+}
+
+/// Convert ecliptic coordinates to right ascension and declination, all in radians
+fn ecliptic_to_equatorial(longitude: f32, latitude: f32) -> (f32, f32) {
+    let (sin_e, cos_e) = (sinf(OBLIQUITY), cosf(OBLIQUITY));
+    let x = cosf(latitude) * cosf(longitude);
+    let y = cosf(latitude) * sinf(longitude) * cos_e - sinf(latitude) * sin_e;
+    let z = cosf(latitude) * sinf(longitude) * sin_e + sinf(latitude) * cos_e;
+    (atan2f(y, x), atan2f(z, sqrtf(x * x + y * y)))
+}
+
+/// Greenwich mean sidereal time in degrees
+fn sidereal_time_degrees(ts: i64) -> f32 {
+    let days = (ts - J2000_UNIX) as f64 / SECS_PER_DAY as f64;
+    normalize_degrees_f64(280.460_618_37 + 360.985_647_366_29 * days) as f32
+}
+
+fn centuries_since_j2000(ts: i64) -> f64 {
+    (ts - J2000_UNIX) as f64 / SECS_PER_DAY as f64 / 36525.0
+}
+
+/// Fractional part, always in `0.0..1.0`
+fn frac(x: f64) -> f64 {
+    let f = x % 1.0;
+    if f < 0.0 { f + 1.0 } else { f }
+}
+
+fn normalize_degrees(x: f32) -> f32 {
+    let r = x % 360.0;
+    if r < 0.0 { r + 360.0 } else { r }
+}
+
+fn normalize_degrees_f64(x: f64) -> f64 {
+    let r = x % 360.0;
+    if r < 0.0 { r + 360.0 } else { r }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Local hours and minutes of `ts`
+    fn local_hh_mm(ts: i64, utc_offset_seconds: i64) -> (i64, i64) {
+        let secs = (ts + utc_offset_seconds).rem_euclid(SECS_PER_DAY);
+        (secs / 3600, secs % 3600 / 60)
+    }
+
+    #[test]
+    fn phase_at_published_full_moon() {
+        // Full moon 2024-01-25 17:54 UTC
+        let moon = moon_info(1_706_205_240, 0, 0.0, 0.0);
+        assert_eq!(moon.phase, MoonPhase::Full);
+        assert!((moon.elongation - 180.0).abs() < 1.0, "{moon:?}");
+        assert!(moon.illumination > 0.999);
+        assert!((moon.age_days() - SYNODIC_MONTH_DAYS / 2.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn phase_at_published_new_moon() {
+        // New moon 2024-04-08 18:21 UTC, the North American total solar eclipse
+        let moon = moon_info(1_712_600_460, 0, 0.0, 0.0);
+        assert_eq!(moon.phase, MoonPhase::New);
+        assert!(moon.elongation < 1.0 || moon.elongation > 359.0, "{moon:?}");
+        assert!(moon.illumination < 0.001);
+    }
+
+    #[test]
+    fn phase_at_published_quarters() {
+        // First quarter 2024-01-18 03:53 UTC, last quarter 2024-02-02 23:18 UTC
+        let first = moon_info(1_705_549_980, 0, 0.0, 0.0);
+        assert_eq!(first.phase, MoonPhase::FirstQuarter);
+        assert!((first.elongation - 90.0).abs() < 1.0, "{first:?}");
+        assert!((first.illumination - 0.5).abs() < 0.01);
+
+        let last = moon_info(1_706_915_880, 0, 0.0, 0.0);
+        assert_eq!(last.phase, MoonPhase::LastQuarter);
+        assert!((last.elongation - 270.0).abs() < 1.0, "{last:?}");
+    }
+
+    #[test]
+    fn position_matches_meeus_example() {
+        // Meeus, "Astronomical Algorithms", example 47.a: 1992-04-12 0h TD (59 s after 0h UT)
+        let t = centuries_since_j2000(703_036_800 - 59);
+        let (longitude, latitude) = moon_ecliptic(t);
+        assert!((longitude.to_degrees() - 133.163).abs() < 0.05);
+        assert!((latitude.to_degrees() - -3.229_126).abs() < 0.05);
+
+        // Meeus gives the apparent position, which adds nutation this series leaves out
+        let (right_ascension, declination) = ecliptic_to_equatorial(longitude, latitude);
+        assert!((right_ascension.to_degrees() - 134.688).abs() < 0.05);
+        assert!((declination.to_degrees() - 13.768_368).abs() < 0.05);
+    }
+
+    #[test]
+    fn sidereal_time_matches_meeus_example() {
+        // Meeus example 12.a: 1987-04-10 0h UT, 13h10m46.3668s
+        assert!((sidereal_time_degrees(545_011_200) - 197.693_2).abs() < 0.001);
+    }
+
+    #[test]
+    fn moonrise_and_moonset_on_eclipse_day() {
+        // On 2024-04-08 the moon passed in front of the sun, so it rose just before the sun
+        // (07:05 CDT in Dallas) and set just after it (19:51 CDT)
+        let cdt = -5 * 3600;
+        let moon = moon_info(1_712_599_200, cdt as i32, 32.78, -96.80);
+        let moonrise = local_hh_mm(moon.moonrise.unwrap(), cdt);
+        let moonset = local_hh_mm(moon.moonset.unwrap(), cdt);
+        assert!(((6, 45)..=(7, 10)).contains(&moonrise), "{moonrise:?}");
+        assert!(((19, 50)..=(20, 20)).contains(&moonset), "{moonset:?}");
+    }
+
+    #[test]
+    fn moonrise_at_full_moon_is_near_sunset() {
+        // Full moon 2024-01-25 in Denver, where the sun set at 17:07 MST
+        let mst = -7 * 3600;
+        let moon = moon_info(1_706_205_240, mst as i32, 39.74, -104.99);
+        let moonrise = local_hh_mm(moon.moonrise.unwrap(), mst);
+        assert!(((16, 45)..=(17, 30)).contains(&moonrise), "{moonrise:?}");
+        // It set that morning, around sunrise
+        let moonset = local_hh_mm(moon.moonset.unwrap(), mst);
+        assert!(((7, 0)..=(8, 0)).contains(&moonset), "{moonset:?}");
+    }
+}
@@ -0,0 +1,79 @@
+use heapless::String;
+use serde::Deserialize;
+use serde_json_core::{self as json_core};
+
+use crate::{
+    config::{IP_GEOLOCATION_HOST, IP_GEOLOCATION_PATH},
+    error::{AppError, Result},
+    network::http::{extract_body, http_get_raw},
+};
+
+const HEADERS_STR: &str = "Accept: application/json";
+
+// Heuristic string capacities
+const BUF_LEN: usize = 32;
+
+/// IP geolocation response struct
+///
+/// Field aliases accept both the ip-api.com (`lat`, `lon`, `regionName`) and the
+/// ipapi.co/ipinfo style (`latitude`, `longitude`, `region`) field names.
+#[derive(Deserialize, Debug)]
+pub struct IpGeolocationResponse {
+    /// ip-api.com reports failures with `"status": "fail"` and HTTP 200
+    pub status: Option<String<BUF_LEN>>,
+    #[serde(alias = "lat")]
+    pub latitude: Option<f32>,
+    #[serde(alias = "lon")]
+    pub longitude: Option<f32>,
+    pub timezone: Option<String<BUF_LEN>>,
+    pub city: Option<String<BUF_LEN>>,
+    #[serde(alias = "regionName")]
+    pub region: Option<String<BUF_LEN>>,
+}
+
+/// Parse the IP geolocation JSON response into an IpGeolocationResponse struct
+impl core::convert::TryFrom<&[u8]> for IpGeolocationResponse {
+    type Error = json_core::de::Error;
+
+    fn try_from(value: &[u8]) -> core::result::Result<Self, Self::Error> {
+        let (parsed, _consumed) = json_core::from_slice::<IpGeolocationResponse>(value)?;
+        Ok(parsed)
+    }
+}
+
+/// Look up the approximate location of the device's public IP address.
+///
+/// Returns a parsed `IpGeolocationResponse` with coordinates on success or an error `Result`
+/// on failure.
+pub async fn fetch_ip_geolocation(
+    stack: embassy_net::Stack<'static>,
+) -> Result<IpGeolocationResponse> {
+    let buf = http_get_raw(
+        stack,
+        IP_GEOLOCATION_HOST,
+        IP_GEOLOCATION_PATH,
+        Some(HEADERS_STR),
+    )
+    .await
+    .map_err(|e| {
+        log::error!("Fetching IP geolocation failed: {:?}", e);
+        e
+    })?;
+
+    let parsed = IpGeolocationResponse::try_from(extract_body(&buf)).map_err(|e| {
+        log::error!("Failed to parse IP geolocation JSON response: {:?}", e);
+        AppError::from(e)
+    })?;
+
+    log::debug!("{parsed:?}");
+
+    if parsed.status.as_deref().is_some_and(|s| s != "success")
+        || parsed.latitude.is_none()
+        || parsed.longitude.is_none()
+    {
+        log::error!("IP geolocation returned no coordinates");
+        return Err(AppError::LocationNotFound);
+    }
+
+    Ok(parsed)
+}
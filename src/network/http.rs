@@ -3,7 +3,7 @@ use embassy_net::tcp::TcpSocket;
 use embassy_time::{Duration, Instant, with_deadline};
 use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
 
-use crate::config::{CONNECT_TIMEOUT, REQUEST_TIMEOUT, RESPONSE_TIMEOUT};
+use crate::config::{CONNECT_TIMEOUT, MAX_RESPONSE_LEN, REQUEST_TIMEOUT, RESPONSE_TIMEOUT};
 use crate::error::{AppError, Result};
 use crate::network::get_ip;
 use alloc::string::String;
@@ -113,7 +113,9 @@ pub(crate) fn build_http_request(
 /// This is a low-level HTTP client function that handles DNS resolution, TCP connection,
 /// sending the request, and reading the response into a fixed-size buffer.
 ///
-/// Returns a buffer containing the raw HTTP response (headers + body).
+/// Returns a buffer containing the raw HTTP response (headers + body),
+/// `AppError::ResponseTooLarge` once it grows past `MAX_RESPONSE_LEN`, or
+/// `AppError::OutOfMemory` when the buffer cannot be allocated.
 pub(crate) async fn http_get_raw(
     stack: embassy_net::Stack<'static>,
     host: &str,
@@ -178,8 +180,16 @@ pub(crate) async fn http_get_raw(
     log::debug!("HTTP request sent");
     log::debug!("Attempting to read response");
 
-    // Read response with a deadline for the whole receive operation. Accumulate into a Vec.
-    let mut resp: Vec<u8> = Vec::with_capacity(1536);
+    // Read response with a deadline for the whole receive operation. Accumulate into a Vec
+    // allocated once at its largest size, as growing it would briefly need half as much again.
+    let mut resp: Vec<u8> = Vec::new();
+    if resp.try_reserve_exact(MAX_RESPONSE_LEN).is_err() {
+        log::error!(
+            "Failed to allocate {} byte response buffer",
+            MAX_RESPONSE_LEN
+        );
+        return Err(AppError::OutOfMemory);
+    }
 
     match with_deadline(Instant::now() + RESPONSE_TIMEOUT, async {
         let mut tmp = [0u8; 512];
@@ -189,6 +199,10 @@ pub(crate) async fn http_get_raw(
                     log::debug!("Received complete HTTP response");
                     break Ok(());
                 }
+                Ok(n) if resp.len() + n > MAX_RESPONSE_LEN => {
+                    log::error!("HTTP response exceeds {} bytes", MAX_RESPONSE_LEN);
+                    break Err(AppError::ResponseTooLarge);
+                }
                 Ok(n) => {
                     log::debug!("Read {} bytes", n);
                     resp.extend_from_slice(&tmp[..n]);
@@ -1,3 +1,4 @@
+pub mod geolocation;
 pub mod http;
 
 use embassy_net::IpAddress;
@@ -0,0 +1,122 @@
+pub mod record;
+
+use core::cell::RefCell;
+
+use embassy_sync::blocking_mutex::{Mutex, raw::CriticalSectionRawMutex};
+use heapless::String;
+
+use self::record::migrate;
+pub use self::record::{DisplayUnits, PASSWORD_LEN, SSID_LEN, Settings};
+use crate::{
+    config::{
+        ALERT_SOURCE, DISPLAY_PRECIP_UNIT, DISPLAY_PRESSURE_UNIT, DISPLAY_TEMP_UNIT,
+        DISPLAY_WIND_UNIT, NETWORK_IP_TIMEOUT_SECS, NETWORK_LINK_TIMEOUT_SECS,
+        OPENMETEO_FORECAST_DAYS, OPENMETEO_FORECAST_HOURS, WIFI_PASSWORD, WIFI_SSID,
+    },
+    error::{AppError, Result},
+    storage::{self, Slot},
+    weather::model::{MAX_FORECAST_DAYS, MAX_HOURS},
+};
+
+const _: () = assert!(WIFI_SSID.len() <= SSID_LEN && WIFI_PASSWORD.len() <= PASSWORD_LEN);
+const _: () = assert!(NETWORK_LINK_TIMEOUT_SECS <= u16::MAX as u64);
+const _: () = assert!(NETWORK_IP_TIMEOUT_SECS <= u16::MAX as u64);
+
+/// Settings in effect, `None` until loaded
+static SETTINGS: Mutex<CriticalSectionRawMutex, RefCell<Option<Settings>>> =
+    Mutex::new(RefCell::new(None));
+
+impl Default for DisplayUnits {
+    fn default() -> Self {
+        DisplayUnits {
+            temperature: DISPLAY_TEMP_UNIT,
+            wind_speed: DISPLAY_WIND_UNIT,
+            precipitation: DISPLAY_PRECIP_UNIT,
+            pressure: DISPLAY_PRESSURE_UNIT,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            wifi_ssid: String::try_from(WIFI_SSID).unwrap_or_default(),
+            wifi_password: String::try_from(WIFI_PASSWORD).unwrap_or_default(),
+            location: None,
+            units: DisplayUnits::default(),
+            forecast_days: OPENMETEO_FORECAST_DAYS as u8,
+            forecast_hours: OPENMETEO_FORECAST_HOURS as u8,
+            network_link_timeout_secs: NETWORK_LINK_TIMEOUT_SECS as u16,
+            network_ip_timeout_secs: NETWORK_IP_TIMEOUT_SECS as u16,
+            alert_source: ALERT_SOURCE,
+        }
+    }
+}
+
+impl Settings {
+    /// Whether every value is within the range the firmware supports
+    pub fn is_valid(&self) -> bool {
+        !self.wifi_ssid.is_empty()
+            && (1..=MAX_FORECAST_DAYS).contains(&(self.forecast_days as usize))
+            && (1..=MAX_HOURS).contains(&(self.forecast_hours as usize))
+            && self.network_link_timeout_secs > 0
+            && self.network_ip_timeout_secs > 0
+    }
+}
+
+/// Current settings, the defaults until `load` has run
+pub fn get() -> Settings {
+    SETTINGS
+        .lock(|settings| settings.borrow().clone())
+        .unwrap_or_default()
+}
+
+/// Units shown on the display
+pub fn display_units() -> DisplayUnits {
+    SETTINGS
+        .lock(|settings| settings.borrow().as_ref().map(|settings| settings.units))
+        .unwrap_or_default()
+}
+
+/// Load the settings saved in flash, migrating a record of an older version and saving it
+/// again in the current one.
+///
+/// Keeps the defaults when nothing is saved or the record cannot be read.
+pub async fn load() {
+    let settings = match storage::load_raw(Slot::Settings).await {
+        Some((version, payload)) => match migrate(version, &payload, &Settings::default()) {
+            Some(settings) if settings.is_valid() => {
+                if version != Slot::Settings.version() {
+                    log::info!("Migrated settings from version {}", version);
+                    if let Err(e) = storage::save(Slot::Settings, &settings).await {
+                        log::warn!("Failed to save migrated settings: {:?}", e);
+                    }
+                }
+                settings
+            }
+            _ => {
+                log::warn!("Discarding settings record version {}", version);
+                Settings::default()
+            }
+        },
+        None => {
+            log::info!("No saved settings, using the defaults");
+            Settings::default()
+        }
+    };
+    SETTINGS.lock(|current| *current.borrow_mut() = Some(settings));
+}
+
+/// Apply `change` to the current settings and save the result.
+///
+/// The settings are left unchanged when the result is invalid or cannot be saved.
+pub async fn update(change: impl FnOnce(&mut Settings)) -> Result<()> {
+    let mut settings = get();
+    change(&mut settings);
+    if !settings.is_valid() {
+        return Err(AppError::InvalidSetting);
+    }
+    storage::save(Slot::Settings, &settings).await?;
+    SETTINGS.lock(|current| *current.borrow_mut() = Some(settings));
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! Layouts of the settings record kept in the config partition and migration between them
+
+use heapless::String;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    location::Location,
+    units::{LengthUnit, PressureUnit, SpeedUnit, TemperatureUnit},
+    weather::alert_source::AlertSource,
+};
+
+// Longest SSID (802.11) and WPA2 passphrase
+pub const SSID_LEN: usize = 32;
+pub const PASSWORD_LEN: usize = 64;
+
+/// Settings that can be changed at runtime, kept in the config partition.
+///
+/// The defaults are the constants in `config`. Any change to the layout bumps the
+/// `Slot::Settings` version, keeps the previous layout for `migrate` and adds an arm there.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Settings {
+    pub wifi_ssid: String<SSID_LEN>,
+    pub wifi_password: String<PASSWORD_LEN>,
+    /// Location used instead of `LOCATIONS` when set
+    pub location: Option<Location>,
+    pub units: DisplayUnits,
+    /// Forecast days including today, 1 to `MAX_FORECAST_DAYS`
+    pub forecast_days: u8,
+    /// Hourly forecast entries, 1 to `MAX_HOURS`
+    pub forecast_hours: u8,
+    pub network_link_timeout_secs: u16,
+    pub network_ip_timeout_secs: u16,
+    /// Added in version 2
+    pub alert_source: AlertSource,
+}
+
+/// Settings record of version 1, before the alert source could be changed at runtime
+#[derive(Deserialize, Debug)]
+struct SettingsV1 {
+    wifi_ssid: String<SSID_LEN>,
+    wifi_password: String<PASSWORD_LEN>,
+    location: Option<Location>,
+    units: DisplayUnits,
+    forecast_days: u8,
+    forecast_hours: u8,
+    network_link_timeout_secs: u16,
+    network_ip_timeout_secs: u16,
+}
+
+/// Units shown on the display, converted on the device from the units fetched
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayUnits {
+    pub temperature: TemperatureUnit,
+    pub wind_speed: SpeedUnit,
+    pub precipitation: LengthUnit,
+    pub pressure: PressureUnit,
+}
+
+/// Settings from a record saved with `version` of the `Slot::Settings` layout, with the fields
+/// added since taken from `defaults`; `None` if it cannot be read
+pub fn migrate(version: u16, payload: &[u8], defaults: &Settings) -> Option<Settings> {
+    match version {
+        1 => serde_json_core::from_slice::<SettingsV1>(payload)
+            .ok()
+            .map(|(v1, _)| Settings {
+                wifi_ssid: v1.wifi_ssid,
+                wifi_password: v1.wifi_password,
+                location: v1.location,
+                units: v1.units,
+                forecast_days: v1.forecast_days,
+                forecast_hours: v1.forecast_hours,
+                network_link_timeout_secs: v1.network_link_timeout_secs,
+                network_ip_timeout_secs: v1.network_ip_timeout_secs,
+                alert_source: defaults.alert_source,
+            }),
+        2 => serde_json_core::from_slice::<Settings>(payload)
+            .ok()
+            .map(|(settings, _)| settings),
+        _ => None,
+    }
+}
@@ -0,0 +1,173 @@
+mod record;
+
+use alloc::vec::Vec;
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex};
+use esp_bootloader_esp_idf::partitions::{
+    self, DataPartitionSubType, PARTITION_TABLE_MAX_LEN, PartitionType,
+};
+use esp_storage::FlashStorage;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::error::{AppError, Result};
+use record::{Partition, SECTOR_SIZE, WriteError};
+
+pub use record::Slot;
+
+/// Flash storage scoped to the data partitions holding the slots
+struct Store {
+    flash: FlashStorage<'static>,
+    cache: Option<Region>,
+    config: Option<Region>,
+}
+
+/// Offset and length of a data partition
+#[derive(Debug, Clone, Copy)]
+struct Region {
+    offset: u32,
+    len: u32,
+}
+
+static STORE: Mutex<CriticalSectionRawMutex, Option<Store>> = Mutex::new(None);
+
+/// Locate the cache and config data partitions and make them available for `load` and `save`.
+///
+/// Without a usable partition every `load` from its slots returns `None` and every `save`
+/// fails.
+pub fn init(flash: esp_hal::peripherals::FLASH<'static>) {
+    let mut flash = FlashStorage::new(flash);
+    let mut pt_buf = [0u8; PARTITION_TABLE_MAX_LEN];
+
+    let pt = match partitions::read_partition_table(&mut flash, &mut pt_buf) {
+        Ok(pt) => pt,
+        Err(e) => {
+            log::error!("Failed to read partition table: {:?}", e);
+            return;
+        }
+    };
+    let region = |partition: Partition| {
+        let entry = pt.iter().find(|entry| {
+            matches!(
+                entry.partition_type(),
+                PartitionType::Data(DataPartitionSubType::Undefined)
+            ) && entry.label_as_str() == partition.label()
+        });
+        match entry {
+            Some(entry) => {
+                log::info!(
+                    "{:?} storage at {:#x} ({} bytes)",
+                    partition,
+                    entry.offset(),
+                    entry.len()
+                );
+                Some(Region {
+                    offset: entry.offset(),
+                    len: entry.len(),
+                })
+            }
+            None => {
+                log::error!(
+                    "No {:?} partition found, its storage is disabled",
+                    partition
+                );
+                None
+            }
+        }
+    };
+
+    let store = Store {
+        cache: region(Partition::Cache),
+        config: region(Partition::Config),
+        flash,
+    };
+    if let Ok(mut guard) = STORE.try_lock() {
+        *guard = Some(store);
+    }
+}
+
+/// Load and deserialize the record in `slot`.
+///
+/// Returns `None` if the slot is empty, corrupt or holds an older record version.
+pub async fn load<T: DeserializeOwned>(slot: Slot) -> Option<T> {
+    let mut guard = STORE.lock().await;
+    let store = guard.as_mut()?;
+    let offset = store.slot_offset(slot).ok()?;
+    record::read(&mut store.flash, offset, slot)
+}
+
+/// Read the version and raw payload of the record in `slot` after validating its header.
+///
+/// Unlike `load`, records of any version are returned, for slots whose older records are
+/// migrated rather than discarded.
+pub async fn load_raw(slot: Slot) -> Option<(u16, Vec<u8>)> {
+    let mut guard = STORE.lock().await;
+    let store = guard.as_mut()?;
+    let offset = store.slot_offset(slot).ok()?;
+    record::read_raw(&mut store.flash, offset, slot)
+}
+
+/// Serialize `value` and write it to `slot`, replacing any previous record.
+pub async fn save<T: Serialize>(slot: Slot, value: &T) -> Result<()> {
+    let mut guard = STORE.lock().await;
+    let store = guard.as_mut().ok_or(AppError::StorageError)?;
+    let offset = store.slot_offset(slot)?;
+    saved(slot, record::write(&mut store.flash, offset, slot, value))
+}
+
+/// Write already serialized `parts` back to back to `slot` as a single record, replacing any
+/// previous record.
+pub async fn save_parts(slot: Slot, parts: &[&[u8]]) -> Result<()> {
+    let mut guard = STORE.lock().await;
+    let store = guard.as_mut().ok_or(AppError::StorageError)?;
+    let offset = store.slot_offset(slot)?;
+    saved(
+        slot,
+        record::write_parts(&mut store.flash, offset, slot, parts),
+    )
+}
+
+/// Log the outcome of writing a record to `slot`
+fn saved<E: core::fmt::Debug>(
+    slot: Slot,
+    result: core::result::Result<usize, WriteError<E>>,
+) -> Result<()> {
+    match result {
+        Ok(len) => {
+            log::info!("Saved {:?} record ({} bytes)", slot, len);
+            Ok(())
+        }
+        Err(WriteError::Serialize) => {
+            log::error!("Failed to serialize {:?} record", slot);
+            Err(AppError::StorageError)
+        }
+        Err(WriteError::TooLarge(len)) => {
+            log::error!(
+                "{:?} record of {} bytes exceeds the {} byte slot",
+                slot,
+                len,
+                slot.capacity()
+            );
+            Err(AppError::StorageError)
+        }
+        Err(WriteError::Flash(e)) => {
+            log::error!("Failed to write {:?} record: {:?}", slot, e);
+            Err(AppError::StorageError)
+        }
+    }
+}
+
+impl Store {
+    /// Absolute flash offset of `slot`, checked against the bounds of its partition
+    fn slot_offset(&self, slot: Slot) -> Result<u32> {
+        let region = match slot.partition() {
+            Partition::Cache => self.cache,
+            Partition::Config => self.config,
+        }
+        .ok_or(AppError::StorageError)?;
+        let (first, count) = slot.sectors();
+        if (first + count) * SECTOR_SIZE > region.len {
+            log::error!("Storage slot exceeds partition size");
+            return Err(AppError::StorageError);
+        }
+        Ok(region.offset + first * SECTOR_SIZE)
+    }
+}
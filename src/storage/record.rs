@@ -0,0 +1,414 @@
+//! Versioned, checksummed records kept in fixed slots of a data partition
+
+use alloc::vec::Vec;
+use embedded_storage::{ReadStorage, Storage};
+use serde::{Serialize, de::DeserializeOwned};
+
+pub const SECTOR_SIZE: u32 = 4096;
+
+// Record header: magic, slot version, payload length, payload checksum
+const MAGIC: u32 = 0x5357_544d; // "MTWS"
+const HEADER_LEN: usize = 12;
+
+/// Data partition a slot is kept in, both of subtype `undefined` so that neither ESP-IDF NVS
+/// nor flashing the app touches them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Partition {
+    /// The `cache` partition, for data that can be fetched again
+    Cache,
+    /// The `config` partition, for settings
+    Config,
+}
+
+impl Partition {
+    /// Label of the partition in `partitions.csv`
+    pub const fn label(self) -> &'static str {
+        match self {
+            Partition::Cache => "cache",
+            Partition::Config => "config",
+        }
+    }
+}
+
+/// A fixed region of the data partition holding a single serialized record
+#[derive(Debug, Clone, Copy)]
+pub enum Slot {
+    /// Locations resolved from place names or IP geolocation
+    Location,
+    /// Last forecast fetched successfully, shown when a later fetch fails
+    Forecast,
+    /// Last multi-location summary fetched successfully, shown when a later fetch fails
+    Summary,
+    /// Runtime settings
+    Settings,
+}
+
+impl Slot {
+    pub const fn partition(self) -> Partition {
+        match self {
+            Slot::Location | Slot::Forecast | Slot::Summary => Partition::Cache,
+            Slot::Settings => Partition::Config,
+        }
+    }
+
+    /// First sector in its partition and number of sectors reserved for the slot
+    pub const fn sectors(self) -> (u32, u32) {
+        match self {
+            Slot::Location => (0, 1),
+            Slot::Forecast => (1, 3),
+            Slot::Summary => (4, 2),
+            Slot::Settings => (0, 1),
+        }
+    }
+
+    /// Version of the record stored in the slot; bump it when the record type changes
+    pub const fn version(self) -> u16 {
+        match self {
+            Slot::Location => 3,
+            Slot::Forecast => 2,
+            Slot::Summary => 1,
+            Slot::Settings => 2,
+        }
+    }
+
+    /// Largest payload that fits in the slot after the record header
+    pub const fn capacity(self) -> usize {
+        let (_, count) = self.sectors();
+        (count * SECTOR_SIZE) as usize - HEADER_LEN
+    }
+}
+
+/// Why a record could not be written
+#[derive(Debug)]
+pub enum WriteError<E> {
+    /// The value could not be serialized into the slot
+    Serialize,
+    /// The payload exceeds the capacity of the slot
+    TooLarge(usize),
+    Flash(E),
+}
+
+/// Deserialize the record in `slot` at `offset` of `flash`.
+///
+/// Returns `None` if the slot is empty, corrupt or holds another record version.
+pub fn read<F: ReadStorage, T: DeserializeOwned>(
+    flash: &mut F,
+    offset: u32,
+    slot: Slot,
+) -> Option<T> {
+    let (version, payload) = read_raw(flash, offset, slot)?;
+    if version != slot.version() {
+        log::info!("Ignoring {:?} record version {}", slot, version);
+        return None;
+    }
+    match serde_json_core::from_slice::<T>(&payload) {
+        Ok((value, _)) => Some(value),
+        Err(e) => {
+            log::warn!("Discarding unreadable {:?} record: {:?}", slot, e);
+            None
+        }
+    }
+}
+
+/// Read the version and raw payload of the record in `slot` at `offset` of `flash` after
+/// validating its header.
+///
+/// Unlike `read`, records of any version are returned, for slots whose older records are
+/// migrated rather than discarded.
+pub fn read_raw<F: ReadStorage>(flash: &mut F, offset: u32, slot: Slot) -> Option<(u16, Vec<u8>)> {
+    let mut header = [0u8; HEADER_LEN];
+    flash.read(offset, &mut header).ok()?;
+    let magic = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    let len = u16::from_le_bytes([header[6], header[7]]) as usize;
+    let expected = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+
+    if magic != MAGIC || len > slot.capacity() {
+        log::info!("No {:?} record stored", slot);
+        return None;
+    }
+    let mut payload = vec![0u8; len];
+    flash.read(offset + HEADER_LEN as u32, &mut payload).ok()?;
+    if checksum(&payload) != expected {
+        log::warn!("{:?} record failed checksum", slot);
+        return None;
+    }
+    Some((version, payload))
+}
+
+/// Serialize `value` and write it to `slot` at `offset` of `flash`, replacing any previous
+/// record. Returns the payload length.
+pub fn write<F: Storage, T: Serialize>(
+    flash: &mut F,
+    offset: u32,
+    slot: Slot,
+    value: &T,
+) -> Result<usize, WriteError<F::Error>> {
+    let mut payload = vec![0u8; slot.capacity()];
+    let len = serde_json_core::to_slice(value, &mut payload).map_err(|_| WriteError::Serialize)?;
+    write_record(flash, offset, slot.version(), &[&payload[..len]])
+}
+
+/// Write already serialized `parts` back to back to `slot` at `offset` of `flash` as a single
+/// record, replacing any previous record. Returns the payload length.
+pub fn write_parts<F: Storage>(
+    flash: &mut F,
+    offset: u32,
+    slot: Slot,
+    parts: &[&[u8]],
+) -> Result<usize, WriteError<F::Error>> {
+    let len: usize = parts.iter().map(|part| part.len()).sum();
+    if len > slot.capacity() {
+        return Err(WriteError::TooLarge(len));
+    }
+    write_record(flash, offset, slot.version(), parts)
+}
+
+/// Write the header and `parts` as `version` of a slot, one sector at a time so that the
+/// payload is never copied whole.
+fn write_record<F: Storage>(
+    flash: &mut F,
+    offset: u32,
+    version: u16,
+    parts: &[&[u8]],
+) -> Result<usize, WriteError<F::Error>> {
+    let len: usize = parts.iter().map(|part| part.len()).sum();
+    let checksum = parts
+        .iter()
+        .fold(FNV_OFFSET_BASIS, |hash, part| fnv1a(hash, part));
+    let mut header = [0u8; HEADER_LEN];
+    header[0..4].copy_from_slice(&MAGIC.to_le_bytes());
+    header[4..6].copy_from_slice(&version.to_le_bytes());
+    header[6..8].copy_from_slice(&(len as u16).to_le_bytes());
+    header[8..12].copy_from_slice(&checksum.to_le_bytes());
+
+    let mut sector = [0u8; SECTOR_SIZE as usize];
+    let mut filled = 0;
+    let mut sector_offset = offset;
+    for part in core::iter::once(header.as_slice()).chain(parts.iter().copied()) {
+        let mut rest = part;
+        while !rest.is_empty() {
+            let n = rest.len().min(sector.len() - filled);
+            sector[filled..filled + n].copy_from_slice(&rest[..n]);
+            filled += n;
+            rest = &rest[n..];
+            if filled == sector.len() {
+                flash
+                    .write(sector_offset, &sector)
+                    .map_err(WriteError::Flash)?;
+                sector_offset += SECTOR_SIZE;
+                filled = 0;
+            }
+        }
+    }
+    if filled > 0 {
+        flash
+            .write(sector_offset, &sector[..filled])
+            .map_err(WriteError::Flash)?;
+    }
+    Ok(len)
+}
+
+const FNV_OFFSET_BASIS: u32 = 0x811c_9dc5;
+
+/// Continue a 32-bit FNV-1a hash, used to detect torn or corrupted records, over `data`
+fn fnv1a(hash: u32, data: &[u8]) -> u32 {
+    data.iter()
+        .fold(hash, |hash, &b| (hash ^ b as u32).wrapping_mul(0x0100_0193))
+}
+
+/// 32-bit FNV-1a hash of `data`
+fn checksum(data: &[u8]) -> u32 {
+    fnv1a(FNV_OFFSET_BASIS, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_storage::ReadStorage;
+    use heapless::String;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{
+        settings::record::{DisplayUnits, Settings, migrate},
+        units::{LengthUnit, PressureUnit, SpeedUnit, TemperatureUnit},
+        weather::alert_source::AlertSource,
+    };
+
+    /// Erased NOR flash held in memory, noting the longest single write
+    struct MemFlash(Vec<u8>, usize);
+
+    impl MemFlash {
+        fn new() -> Self {
+            MemFlash(vec![0xff; 2 * SECTOR_SIZE as usize], 0)
+        }
+    }
+
+    #[derive(Debug)]
+    struct OutOfBounds;
+
+    impl ReadStorage for MemFlash {
+        type Error = OutOfBounds;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let start = offset as usize;
+            let data = self.0.get(start..start + bytes.len()).ok_or(OutOfBounds)?;
+            bytes.copy_from_slice(data);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    impl Storage for MemFlash {
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            let start = offset as usize;
+            let data = self
+                .0
+                .get_mut(start..start + bytes.len())
+                .ok_or(OutOfBounds)?;
+            data.copy_from_slice(bytes);
+            self.1 = self.1.max(bytes.len());
+            Ok(())
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Place {
+        name: String<16>,
+        latitude: f32,
+    }
+
+    const OFFSET: u32 = SECTOR_SIZE;
+
+    fn denver() -> Place {
+        Place {
+            name: String::try_from("Denver").unwrap(),
+            latitude: 39.74,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut flash = MemFlash::new();
+        assert_eq!(read::<_, Place>(&mut flash, OFFSET, Slot::Location), None);
+
+        write(&mut flash, OFFSET, Slot::Location, &denver()).unwrap();
+        assert_eq!(
+            read::<_, Place>(&mut flash, OFFSET, Slot::Location),
+            Some(denver())
+        );
+
+        let parts: [&[u8]; 3] = [b"{\"name\":", b"\"Boulder\"", b",\"latitude\":40.01}"];
+        write_parts(&mut flash, OFFSET, Slot::Location, &parts).unwrap();
+        let place = read::<_, Place>(&mut flash, OFFSET, Slot::Location).unwrap();
+        assert_eq!(place.name, "Boulder");
+    }
+
+    #[test]
+    fn record_spanning_sectors_is_written_a_sector_at_a_time() {
+        let mut flash = MemFlash::new();
+        let body = [b'x'; 5000];
+        let parts: [&[u8]; 3] = [b"{\"body\":\"", &body, b"\"}"];
+        let len = write_parts(&mut flash, 0, Slot::Summary, &parts).unwrap();
+        assert_eq!(len, body.len() + 11);
+        assert_eq!(flash.1, SECTOR_SIZE as usize);
+
+        let (version, raw) = read_raw(&mut flash, 0, Slot::Summary).unwrap();
+        assert_eq!(version, Slot::Summary.version());
+        assert_eq!(raw, parts.concat());
+    }
+
+    #[test]
+    fn corrupt_record_fails_checksum() {
+        let mut flash = MemFlash::new();
+        let len = write(&mut flash, OFFSET, Slot::Location, &denver()).unwrap();
+        // Flip a bit in the last payload byte, as a torn write would leave it
+        flash.0[OFFSET as usize + HEADER_LEN + len - 1] ^= 0x01;
+        assert_eq!(read_raw(&mut flash, OFFSET, Slot::Location), None);
+        assert_eq!(read::<_, Place>(&mut flash, OFFSET, Slot::Location), None);
+    }
+
+    #[test]
+    fn other_version_is_only_read_raw() {
+        let mut flash = MemFlash::new();
+        let payload = b"{\"name\":\"Denver\",\"latitude\":39.74}";
+        write_record(&mut flash, OFFSET, 2, &[payload]).unwrap();
+
+        assert_eq!(read::<_, Place>(&mut flash, OFFSET, Slot::Location), None);
+        let (version, raw) = read_raw(&mut flash, OFFSET, Slot::Location).unwrap();
+        assert_eq!(version, 2);
+        assert_eq!(raw, payload);
+    }
+
+    #[test]
+    fn oversized_payload_is_rejected() {
+        let mut flash = MemFlash::new();
+        let big = [b' '; SECTOR_SIZE as usize];
+        assert!(matches!(
+            write_parts(&mut flash, OFFSET, Slot::Location, &[&big]),
+            Err(WriteError::TooLarge(len)) if len == big.len()
+        ));
+        assert!(matches!(
+            write_parts(&mut MemFlash(vec![0; 4], 0), 0, Slot::Location, &[b"{}"]),
+            Err(WriteError::Flash(OutOfBounds))
+        ));
+    }
+
+    /// Settings record as written by the firmware before version 2 added the alert source
+    const SETTINGS_V1: &str = concat!(
+        r#"{"wifi_ssid":"home","wifi_password":"secret","#,
+        r#""location":{"name":"Denver","latitude":"39.7392","longitude":"-104.9847","#,
+        r#""timezone":"America/Denver"},"#,
+        r#""units":{"temperature":"Fahrenheit","wind_speed":"MilesPerHour","#,
+        r#""precipitation":"Inch","pressure":"InchOfMercury"},"#,
+        r#""forecast_days":5,"forecast_hours":24,"#,
+        r#""network_link_timeout_secs":30,"network_ip_timeout_secs":15}"#,
+    );
+
+    #[test]
+    fn settings_v1_record_migrates_to_v2() {
+        let mut flash = MemFlash::new();
+        write_record(&mut flash, OFFSET, 1, &[SETTINGS_V1.as_bytes()]).unwrap();
+
+        // A version 1 record is not read as the current layout, which adds `alert_source`
+        assert!(read::<_, Settings>(&mut flash, OFFSET, Slot::Settings).is_none());
+
+        // Fields the record holds are kept, the alert source comes from the defaults
+        let defaults = Settings {
+            wifi_ssid: String::try_from("default").unwrap(),
+            wifi_password: String::new(),
+            location: None,
+            units: DisplayUnits {
+                temperature: TemperatureUnit::Celsius,
+                wind_speed: SpeedUnit::KilometresPerHour,
+                precipitation: LengthUnit::Millimetre,
+                pressure: PressureUnit::Hectopascal,
+            },
+            forecast_days: 7,
+            forecast_hours: 12,
+            network_link_timeout_secs: 30,
+            network_ip_timeout_secs: 30,
+            alert_source: AlertSource::Nws,
+        };
+        let (version, raw) = read_raw(&mut flash, OFFSET, Slot::Settings).unwrap();
+        assert_eq!(version, 1);
+        let migrated = migrate(version, &raw, &defaults).unwrap();
+        assert_eq!(migrated.wifi_ssid, "home");
+        assert_eq!(migrated.wifi_password, "secret");
+        assert_eq!(migrated.location.as_ref().unwrap().name, "Denver");
+        assert_eq!(migrated.units.wind_speed, SpeedUnit::MilesPerHour);
+        assert_eq!(migrated.forecast_days, 5);
+        assert_eq!(migrated.network_ip_timeout_secs, 15);
+        assert_eq!(migrated.alert_source, AlertSource::Nws);
+
+        // Saved again in the current version, it reads back directly
+        write(&mut flash, OFFSET, Slot::Settings, &migrated).unwrap();
+        let settings = read::<_, Settings>(&mut flash, OFFSET, Slot::Settings).unwrap();
+        assert_eq!(settings.wifi_ssid, "home");
+        assert_eq!(settings.units, migrated.units);
+        assert_eq!(settings.alert_source, AlertSource::Nws);
+    }
+}
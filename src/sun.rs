@@ -0,0 +1,239 @@
+use libm::{acosf, asinf, cosf, sinf, tanf};
+
+const SECS_PER_DAY: i64 = 86_400;
+/// Julian day of the unix epoch
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+/// Zenith angle of the sun's centre at sunrise and sunset, including refraction
+const SUNRISE_ZENITH: f32 = 90.833;
+/// Zenith angle of the sun's centre at the start and end of civil twilight
+const CIVIL_TWILIGHT_ZENITH: f32 = 96.0;
+
+/// Returns the sunrise and sunset on the local day containing `ts`, computed with the NOAA
+/// solar algorithm for `latitude` and `longitude` in degrees.
+///
+/// `utc_offset_seconds` selects the local day. Returns `None` during polar night or midnight
+/// sun, when the sun does not cross the horizon.
+pub fn sunrise_sunset_unix(
+    ts: i64,
+    utc_offset_seconds: i32,
+    latitude: f32,
+    longitude: f32,
+) -> Option<(i64, i64)> {
+    horizon_crossings(ts, utc_offset_seconds, latitude, longitude, SUNRISE_ZENITH)
+}
+
+/// Returns the start (dawn) and end (dusk) of civil twilight on the local day containing `ts`.
+///
+/// Returns `None` when the sun stays above or below 6 degrees under the horizon all day.
+pub fn civil_twilight_unix(
+    ts: i64,
+    utc_offset_seconds: i32,
+    latitude: f32,
+    longitude: f32,
+) -> Option<(i64, i64)> {
+    horizon_crossings(
+        ts,
+        utc_offset_seconds,
+        latitude,
+        longitude,
+        CIVIL_TWILIGHT_ZENITH,
+    )
+}
+
+/// Returns the time between sunrise and sunset on the local day containing `ts`, in seconds.
+///
+/// Polar night gives `0` and midnight sun gives a full day.
+pub fn day_length_secs(ts: i64, utc_offset_seconds: i32, latitude: f32, longitude: f32) -> u32 {
+    let day = local_day(ts, utc_offset_seconds);
+    let position = solar_position(day, longitude);
+    match hour_angle_degrees(latitude, position.declination, SUNRISE_ZENITH) {
+        Ok(hour_angle) => (hour_angle * 2.0 * 240.0) as u32,
+        Err(HorizonError::NeverRises) => 0,
+        Err(HorizonError::NeverSets) => SECS_PER_DAY as u32,
+    }
+}
+
+/// Declination and solar noon for one day
+struct SolarPosition {
+    /// Radians
+    declination: f32,
+    /// Minutes after UTC midnight
+    solar_noon: f32,
+}
+
+enum HorizonError {
+    NeverRises,
+    NeverSets,
+}
+
+/// Days since the unix epoch of the local calendar day containing `ts`
+fn local_day(ts: i64, utc_offset_seconds: i32) -> i64 {
+    (ts + utc_offset_seconds as i64).div_euclid(SECS_PER_DAY)
+}
+
+fn horizon_crossings(
+    ts: i64,
+    utc_offset_seconds: i32,
+    latitude: f32,
+    longitude: f32,
+    zenith: f32,
+) -> Option<(i64, i64)> {
+    let day = local_day(ts, utc_offset_seconds);
+    let position = solar_position(day, longitude);
+    let hour_angle = hour_angle_degrees(latitude, position.declination, zenith).ok()?;
+
+    // Four minutes of time per degree of hour angle
+    let to_unix = |minutes: f32| day * SECS_PER_DAY + (minutes * 60.0) as i64;
+    Some((
+        to_unix(position.solar_noon - 4.0 * hour_angle),
+        to_unix(position.solar_noon + 4.0 * hour_angle),
+    ))
+}
+
+/// Hour angle in degrees between solar noon and the sun reaching `zenith`
+fn hour_angle_degrees(latitude: f32, declination: f32, zenith: f32) -> Result<f32, HorizonError> {
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = cosf(zenith.to_radians()) / (cosf(latitude) * cosf(declination))
+        - tanf(latitude) * tanf(declination);
+    if cos_hour_angle > 1.0 {
+        return Err(HorizonError::NeverRises);
+    }
+    if cos_hour_angle < -1.0 {
+        return Err(HorizonError::NeverSets);
+    }
+    Ok(acosf(cos_hour_angle).to_degrees())
+}
+
+/// NOAA solar position for local noon of `day` at `longitude`
+fn solar_position(day: i64, longitude: f32) -> SolarPosition {
+    let julian_day = UNIX_EPOCH_JD + day as f64 + (720.0 - 4.0 * longitude as f64) / 1440.0;
+    let t = (julian_day - 2_451_545.0) / 36_525.0;
+
+    // Reduce the large angles in f64 before switching to f32
+    let mean_longitude = normalize_degrees(280.466_46 + t * (36_000.769_83 + t * 0.000_303_2));
+    let mean_anomaly = normalize_degrees(357.529_11 + t * (35_999.050_29 - 0.000_153_7 * t));
+    let omega = normalize_degrees(125.04 - 1_934.136 * t).to_radians();
+    let t = t as f32;
+
+    let eccentricity = 0.016_708_634 - t * (0.000_042_037 + 0.000_000_126_7 * t);
+    let m = mean_anomaly.to_radians();
+    let center = sinf(m) * (1.914_602 - t * (0.004_817 + 0.000_014 * t))
+        + sinf(2.0 * m) * (0.019_993 - 0.000_101 * t)
+        + sinf(3.0 * m) * 0.000_289;
+    let apparent_longitude =
+        (mean_longitude + center - 0.005_69 - 0.004_78 * sinf(omega)).to_radians();
+
+    let mean_obliquity =
+        23.0 + (26.0 + (21.448 - t * (46.815 + t * (0.000_59 - t * 0.001_813))) / 60.0) / 60.0;
+    let obliquity = (mean_obliquity + 0.002_56 * cosf(omega)).to_radians();
+    let declination = asinf(sinf(obliquity) * sinf(apparent_longitude));
+
+    let y = tanf(obliquity / 2.0) * tanf(obliquity / 2.0);
+    let l0 = mean_longitude.to_radians();
+    let equation_of_time = 4.0
+        * (y * sinf(2.0 * l0) - 2.0 * eccentricity * sinf(m)
+            + 4.0 * eccentricity * y * sinf(m) * cosf(2.0 * l0)
+            - 0.5 * y * y * sinf(4.0 * l0)
+            - 1.25 * eccentricity * eccentricity * sinf(2.0 * m))
+        .to_degrees();
+
+    SolarPosition {
+        declination,
+        solar_noon: 720.0 - 4.0 * longitude - equation_of_time,
+    }
+}
+
+fn normalize_degrees(x: f64) -> f32 {
+    let r = x % 360.0;
+    (if r < 0.0 { r + 360.0 } else { r }) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts that `actual` is within two minutes of the NOAA solar calculator time `expected`
+    fn assert_near(actual: i64, expected: i64) {
+        assert!(
+            (actual - expected).abs() <= 120,
+            "{actual} is {} s from {expected}",
+            actual - expected
+        );
+    }
+
+    // Noon local time on each day
+    const DENVER_JUNE_20: i64 = 1_718_906_400;
+    const GREENWICH_MARCH_20: i64 = 1_710_936_000;
+    const SYDNEY_DECEMBER_21: i64 = 1_734_742_800;
+    const TROMSO_JUNE_21: i64 = 1_718_967_600;
+    const TROMSO_DECEMBER_21: i64 = 1_734_778_800;
+
+    const MDT: i32 = -6 * 3600;
+    const AEDT: i32 = 11 * 3600;
+    const CET: i32 = 3600;
+    const CEST: i32 = 2 * 3600;
+
+    #[test]
+    fn sunrise_sunset_matches_noaa() {
+        // Denver, 2024-06-20: 05:31 and 20:31 MDT
+        let (sunrise, sunset) = sunrise_sunset_unix(DENVER_JUNE_20, MDT, 39.74, -104.99).unwrap();
+        assert_near(sunrise, 1_718_883_060);
+        assert_near(sunset, 1_718_937_060);
+
+        // Greenwich, 2024-03-20: 06:03 and 18:14 UTC
+        let (sunrise, sunset) = sunrise_sunset_unix(GREENWICH_MARCH_20, 0, 51.48, 0.0).unwrap();
+        assert_near(sunrise, 1_710_914_580);
+        assert_near(sunset, 1_710_958_440);
+
+        // Sydney, 2024-12-21: 05:41 and 20:05 AEDT
+        let (sunrise, sunset) =
+            sunrise_sunset_unix(SYDNEY_DECEMBER_21, AEDT, -33.87, 151.21).unwrap();
+        assert_near(sunrise, 1_734_720_060);
+        assert_near(sunset, 1_734_771_900);
+    }
+
+    #[test]
+    fn civil_twilight_matches_noaa() {
+        // Denver, 2024-06-20: 04:59 and 21:04 MDT
+        let (dawn, dusk) = civil_twilight_unix(DENVER_JUNE_20, MDT, 39.74, -104.99).unwrap();
+        assert_near(dawn, 1_718_881_140);
+        assert_near(dusk, 1_718_939_040);
+    }
+
+    #[test]
+    fn day_length_matches_noaa() {
+        // Denver, 2024-06-20: 15:00
+        let length = day_length_secs(DENVER_JUNE_20, MDT, 39.74, -104.99);
+        assert!((length as i64 - 15 * 3600).abs() <= 120, "{length}");
+        // Greenwich, 2024-03-20: 12:11
+        let length = day_length_secs(GREENWICH_MARCH_20, 0, 51.48, 0.0);
+        assert!(
+            (length as i64 - (12 * 3600 + 11 * 60)).abs() <= 120,
+            "{length}"
+        );
+    }
+
+    #[test]
+    fn polar_day_has_no_sunrise() {
+        assert_eq!(
+            sunrise_sunset_unix(TROMSO_JUNE_21, CEST, 69.65, 18.96),
+            None
+        );
+        assert_eq!(
+            civil_twilight_unix(TROMSO_JUNE_21, CEST, 69.65, 18.96),
+            None
+        );
+        assert_eq!(day_length_secs(TROMSO_JUNE_21, CEST, 69.65, 18.96), 86_400);
+    }
+
+    #[test]
+    fn polar_night_has_no_sunrise() {
+        assert_eq!(
+            sunrise_sunset_unix(TROMSO_DECEMBER_21, CET, 69.65, 18.96),
+            None
+        );
+        assert_eq!(day_length_secs(TROMSO_DECEMBER_21, CET, 69.65, 18.96), 0);
+        // The sun still comes within 6 degrees of the horizon around noon
+        assert!(civil_twilight_unix(TROMSO_DECEMBER_21, CET, 69.65, 18.96).is_some());
+    }
+}
@@ -0,0 +1,207 @@
+use core::sync::atomic::Ordering;
+
+use embassy_net::Stack;
+use embassy_time::{Duration, with_timeout};
+use esp_hal::{
+    peripherals::{GPIO44, UART0},
+    uart::{self, UartRx},
+};
+use heapless::String;
+
+use crate::{
+    CONSOLE_ACTIVE, SLEEP_REQUEST,
+    config::CONSOLE_IDLE_SECS,
+    console::{self, CommandError, Device, Input, Key, LineBuffer, LocationValue, Value, execute},
+    error::{AppError, Result, error_history, record_error},
+    location::{Location, resolve_location, resolve_location_offline},
+    settings::{self, Settings},
+    tasks::sleep::SleepReason,
+    weather::{
+        alert_source::AlertSource, api::fetch_weather, cache::load_forecast, model::ForecastSummary,
+    },
+};
+
+pub(crate) struct ConsoleResources {
+    pub uart: UART0<'static>,
+    pub rx: GPIO44<'static>,
+}
+
+/// Console output, written to the serial port shared with the log
+struct SerialOut;
+
+impl core::fmt::Write for SerialOut {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        esp_println::print!("{}", s);
+        Ok(())
+    }
+}
+
+/// Console commands carried out on the device
+struct Board {
+    stack: Stack<'static>,
+}
+
+impl Device for Board {
+    type Settings = Settings;
+    type Forecast = ForecastSummary;
+    type Error = AppError;
+
+    fn settings(&self) -> Settings {
+        settings::get()
+    }
+
+    async fn save_settings(&mut self, new: Settings) -> Result<()> {
+        settings::update(|settings| *settings = new).await
+    }
+
+    async fn fetch(&mut self) -> Result<ForecastSummary> {
+        let result = match resolve_location(self.stack).await {
+            Ok(location) => fetch_weather(self.stack, &location).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = &result {
+            record_error(e);
+        }
+        result.map(|forecast| (&*forecast).into())
+    }
+
+    async fn saved_forecast(&mut self) -> Option<ForecastSummary> {
+        let location = resolve_location_offline().await?;
+        load_forecast(&location)
+            .await
+            .map(|(_, forecast)| (&*forecast).into())
+    }
+
+    fn error_history(&self) -> impl IntoIterator<Item = (u32, impl core::fmt::Display)> {
+        error_history()
+            .into_iter()
+            .map(|error| (error.number, error.message))
+    }
+
+    fn sleep(&mut self, secs: u64) {
+        SLEEP_REQUEST.signal((secs, SleepReason::Console));
+    }
+}
+
+impl console::Settings for Settings {
+    fn get(&self, key: Key) -> Value<'_> {
+        let units = &self.units;
+        match key {
+            Key::WifiSsid => Value::Text(&self.wifi_ssid),
+            Key::WifiPassword => Value::Text(&self.wifi_password),
+            Key::Location => {
+                Value::Location(self.location.as_ref().map(|location| LocationValue {
+                    latitude: &location.latitude,
+                    longitude: &location.longitude,
+                    timezone: &location.timezone,
+                    name: &location.name,
+                }))
+            }
+            Key::TemperatureUnit => Value::Temperature(units.temperature),
+            Key::WindUnit => Value::Wind(units.wind_speed),
+            Key::PrecipUnit => Value::Precip(units.precipitation),
+            Key::PressureUnit => Value::Pressure(units.pressure),
+            Key::ForecastDays => Value::Number(self.forecast_days.into()),
+            Key::ForecastHours => Value::Number(self.forecast_hours.into()),
+            Key::LinkTimeoutSecs => Value::Number(self.network_link_timeout_secs),
+            Key::IpTimeoutSecs => Value::Number(self.network_ip_timeout_secs),
+            Key::AlertSource => Value::Text(self.alert_source.name()),
+        }
+    }
+
+    fn set(&mut self, key: Key, value: Value) -> core::result::Result<(), CommandError> {
+        fn text<const N: usize>(value: &str) -> core::result::Result<String<N>, CommandError> {
+            String::try_from(value).map_err(|_| CommandError::InvalidValue)
+        }
+        fn small(value: u16) -> core::result::Result<u8, CommandError> {
+            value.try_into().map_err(|_| CommandError::InvalidValue)
+        }
+
+        let units = &mut self.units;
+        let invalid = CommandError::InvalidValue;
+        match (key, value) {
+            (Key::WifiSsid, Value::Text(ssid)) => self.wifi_ssid = text(ssid)?,
+            (Key::WifiPassword, Value::Text(password)) => self.wifi_password = text(password)?,
+            (Key::Location, Value::Location(None)) => self.location = None,
+            (Key::Location, Value::Location(Some(location))) => {
+                self.location = Some(Location {
+                    name: text(location.name)?,
+                    latitude: text(location.latitude)?,
+                    longitude: text(location.longitude)?,
+                    timezone: text(location.timezone)?,
+                })
+            }
+            (Key::TemperatureUnit, Value::Temperature(unit)) => units.temperature = unit,
+            (Key::WindUnit, Value::Wind(unit)) => units.wind_speed = unit,
+            (Key::PrecipUnit, Value::Precip(unit)) => units.precipitation = unit,
+            (Key::PressureUnit, Value::Pressure(unit)) => units.pressure = unit,
+            (Key::ForecastDays, Value::Number(days)) => self.forecast_days = small(days)?,
+            (Key::ForecastHours, Value::Number(hours)) => self.forecast_hours = small(hours)?,
+            (Key::LinkTimeoutSecs, Value::Number(secs)) => self.network_link_timeout_secs = secs,
+            (Key::IpTimeoutSecs, Value::Number(secs)) => self.network_ip_timeout_secs = secs,
+            (Key::AlertSource, Value::Text(name)) => {
+                self.alert_source = AlertSource::parse(name).ok_or(invalid)?
+            }
+            _ => return Err(invalid),
+        }
+        Ok(())
+    }
+}
+
+/// Read command lines from the serial console and run them.
+///
+/// The first byte received opens a session that holds off deep sleep until the `sleep`
+/// command or `CONSOLE_IDLE_SECS` without input.
+#[embassy_executor::task]
+pub(crate) async fn console_task(resources: ConsoleResources, stack: Stack<'static>) {
+    let mut rx = match UartRx::new(resources.uart, uart::Config::default()) {
+        Ok(rx) => rx.with_rx(resources.rx).into_async(),
+        Err(e) => {
+            log::error!("Failed to initialize console UART: {:?}", e);
+            return;
+        }
+    };
+    let mut device = Board { stack };
+    let mut line = LineBuffer::default();
+    let mut buf = [0u8; 16];
+
+    loop {
+        let len = match with_timeout(
+            Duration::from_secs(CONSOLE_IDLE_SECS),
+            rx.read_async(&mut buf),
+        )
+        .await
+        {
+            Ok(Ok(len)) => len,
+            Ok(Err(e)) => {
+                log::warn!("Console read failed: {:?}", e);
+                continue;
+            }
+            Err(_) => {
+                if CONSOLE_ACTIVE.load(Ordering::Relaxed) {
+                    log::info!("Console idle, closing session");
+                    CONSOLE_ACTIVE.store(false, Ordering::Relaxed);
+                    SLEEP_REQUEST.signal((0, SleepReason::ConsoleIdle));
+                }
+                continue;
+            }
+        };
+
+        if !CONSOLE_ACTIVE.load(Ordering::Relaxed) {
+            log::info!("Console session opened, type \"help\" for commands");
+            CONSOLE_ACTIVE.store(true, Ordering::Relaxed);
+        }
+        for &byte in &buf[..len] {
+            match line.push(byte) {
+                Input::Added(c) => esp_println::print!("{}", c),
+                Input::Erased => esp_println::print!("\x08 \x08"),
+                Input::Line(command) => {
+                    esp_println::println!();
+                    let _ = execute(&command, &mut device, &mut SerialOut).await;
+                    esp_println::print!("> ");
+                }
+                Input::Ignored => {}
+            }
+        }
+    }
+}
@@ -12,10 +12,12 @@ use esp_hal::{
 
 use crate::{
     DATA_CHANNEL, NETWORK_ERROR, SLEEP_REQUEST,
-    config::SLEEP_ON_ERROR_SECS,
+    config::{SLEEP_DURING_ALERT_SECS, SLEEP_ON_ERROR_SECS},
     display::{display_error_text, display_weather},
+    error::record_error,
     tasks::sleep::SleepReason,
     time::secs_until_6am,
+    weather::alerts::AlertSeverity,
 };
 
 pub(crate) struct DisplayResources {
@@ -68,17 +70,32 @@ pub(crate) async fn display_task(resources: DisplayResources) {
             display_error_text(&err_msg, spi_device, busy, dc, rst);
             SLEEP_REQUEST.signal((SLEEP_ON_ERROR_SECS, SleepReason::NetworkError));
         }
-        Either::Second(weather_data) => {
-            let sleep_secs =
-                secs_until_6am(weather_data.current.time, weather_data.utc_offset_seconds);
+        Either::Second(screen) => {
+            let (current_time, utc_offset_seconds) = screen.current_time();
+            let mut sleep_secs = secs_until_6am(current_time, utc_offset_seconds);
+            // Check back sooner while a dangerous alert is in effect
+            if screen
+                .active_alert()
+                .is_some_and(|alert| alert.severity >= AlertSeverity::Severe)
+            {
+                sleep_secs = sleep_secs.min(SLEEP_DURING_ALERT_SECS);
+            }
+
+            // Retry soon when showing a saved forecast because the fetch failed
+            let stale = screen.is_stale();
 
-            match display_weather(weather_data, spi_device, busy, dc, rst) {
+            match display_weather(screen, spi_device, busy, dc, rst) {
+                Ok(_) if stale => {
+                    log::info!("Saved weather displayed, sleeping...");
+                    SLEEP_REQUEST.signal((SLEEP_ON_ERROR_SECS, SleepReason::NetworkError));
+                }
                 Ok(_) => {
                     log::info!("Weather display successful, sleeping...");
                     SLEEP_REQUEST.signal((sleep_secs, SleepReason::Success));
                 }
                 Err(e) => {
                     log::error!("Displaying weather failed: {:?}", e);
+                    record_error(&e);
                     SLEEP_REQUEST.signal((SLEEP_ON_ERROR_SECS, SleepReason::DisplayError));
                 }
             }
@@ -1,3 +1,4 @@
+pub mod console;
 pub mod display;
 pub mod network;
 pub mod sleep;
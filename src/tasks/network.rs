@@ -3,13 +3,10 @@ use embassy_time::{Duration, Instant, Timer, with_deadline};
 use esp_hal::rng::Rng;
 use esp_radio::wifi::{Config, Interface, WifiController, sta::StationConfig};
 
-use core::fmt::Write;
-use heapless::String;
-
 use crate::{
-    NETWORK_ERROR, NETWORK_READY,
-    config::{NETWORK_IP_TIMEOUT_SECS, NETWORK_LINK_TIMEOUT_SECS, WIFI_PASSWORD, WIFI_SSID},
-    error::{AppError, Result},
+    NETWORK_READY,
+    error::{AppError, Result, record_error},
+    settings,
 };
 
 #[embassy_executor::task]
@@ -21,11 +18,15 @@ pub(crate) async fn wifi_task(mut controller: WifiController<'static>) {
             Timer::after(Duration::from_secs(5)).await;
             continue;
         }
-        log::info!("Attempting to connect to WiFi network SSID: {}", WIFI_SSID);
+        let settings = settings::get();
+        log::info!(
+            "Attempting to connect to WiFi network SSID: {}",
+            settings.wifi_ssid
+        );
         let client_config = Config::Station(
             StationConfig::default()
-                .with_ssid(WIFI_SSID)
-                .with_password(WIFI_PASSWORD.into()),
+                .with_ssid(settings.wifi_ssid.as_str())
+                .with_password(settings.wifi_password.as_str().into()),
         );
         if let Err(e) = controller.set_config(&client_config) {
             log::error!("Failed to set WiFi config: {:?}", e);
@@ -50,8 +51,9 @@ pub(crate) async fn net_runner_task(mut runner: Runner<'static, Interface<'stati
 
 #[embassy_executor::task]
 pub(crate) async fn net_validator_task(stack: embassy_net::Stack<'static>) {
+    let settings = settings::get();
     if with_deadline(
-        Instant::now() + Duration::from_secs(NETWORK_LINK_TIMEOUT_SECS),
+        Instant::now() + Duration::from_secs(settings.network_link_timeout_secs as u64),
         async {
             loop {
                 if stack.is_link_up() {
@@ -65,19 +67,18 @@ pub(crate) async fn net_validator_task(stack: embassy_net::Stack<'static>) {
     .is_err()
     {
         log::error!("Link failed");
-        let mut msg: String<128> = String::new();
-        let _ = write!(msg, "Network link failed");
-        NETWORK_ERROR.signal(msg);
+        record_error(&AppError::LinkFailed);
+        NETWORK_READY.signal(Err(AppError::LinkFailed));
         return;
     }
 
     if with_deadline(
-        Instant::now() + Duration::from_secs(NETWORK_IP_TIMEOUT_SECS),
+        Instant::now() + Duration::from_secs(settings.network_ip_timeout_secs as u64),
         async {
             loop {
                 if let Some(config) = stack.config_v4() {
                     log::info!("Network ready with IP: {}", config.address);
-                    NETWORK_READY.signal(());
+                    NETWORK_READY.signal(Ok(()));
                     break;
                 }
                 Timer::after(Duration::from_millis(500)).await;
@@ -88,9 +89,8 @@ pub(crate) async fn net_validator_task(stack: embassy_net::Stack<'static>) {
     .is_err()
     {
         log::error!("Timed out waiting for IP address");
-        let mut msg: String<128> = String::new();
-        let _ = write!(msg, "Timed out waiting for IP address");
-        NETWORK_ERROR.signal(msg);
+        record_error(&AppError::DhcpTimeout);
+        NETWORK_READY.signal(Err(AppError::DhcpTimeout));
     }
 }
 
@@ -104,10 +104,8 @@ pub(crate) fn init_radio(
     let (controller, interfaces) = match esp_radio::wifi::new(resources.wifi, Default::default()) {
         Ok(wifi) => wifi,
         Err(e) => {
-            let mut msg: String<128> = String::new();
-            let _ = write!(msg, "Failed to initialize WiFi: {:?}", e);
-            log::error!("{msg}");
-            NETWORK_ERROR.signal(msg);
+            log::error!("Failed to initialize WiFi: {:?}", e);
+            record_error(&AppError::ConnectionFailed);
             return Err(AppError::ConnectionFailed);
         }
     };
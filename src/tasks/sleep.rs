@@ -1,11 +1,19 @@
-use core::time::Duration;
+use core::{sync::atomic::Ordering, time::Duration};
 
 use esp_hal::{
-    peripherals::LPWR,
-    rtc_cntl::{Rtc, sleep::TimerWakeupSource},
+    gpio::{RtcPin, RtcPinWithResistors},
+    peripherals::{GPIO11, GPIO15, LPWR},
+    rtc_cntl::{
+        Rtc, SleepSource,
+        sleep::{Ext0WakeupSource, Ext1WakeupSource, TimerWakeupSource, WakeupLevel},
+        wakeup_cause,
+    },
 };
 
-use crate::SLEEP_REQUEST;
+use crate::{
+    CONSOLE_ACTIVE, SLEEP_REQUEST,
+    location::{select_next_location, select_previous_location},
+};
 
 #[derive(Debug)]
 pub(crate) enum SleepReason {
@@ -13,13 +21,67 @@ pub(crate) enum SleepReason {
     HardwareInitError,
     DisplayError,
     NetworkError,
+    /// The `sleep` console command
+    Console,
+    /// The console session went idle, releasing a sleep request held off while it was open
+    ConsoleIdle,
+}
+
+pub(crate) struct SleepResources {
+    pub lpwr: LPWR<'static>,
+    /// Button D, wakes the device on the next saved location
+    pub next_button: GPIO11<'static>,
+    /// Button A, wakes the device on the previous saved location
+    pub previous_button: GPIO15<'static>,
+}
+
+/// Switch the active location when the device was woken by one of the front buttons.
+pub(crate) fn handle_wakeup() {
+    match wakeup_cause() {
+        SleepSource::Ext0 => select_next_location(),
+        SleepSource::Ext1 => select_previous_location(),
+        cause => log::info!("Wakeup cause: {cause:?}"),
+    }
 }
 
 #[embassy_executor::task]
-pub(crate) async fn deep_sleep_task(lpwr: LPWR<'static>) {
-    let mut rtc = Rtc::new(lpwr);
-    let (sleep_seconds, reason) = SLEEP_REQUEST.wait().await;
+pub(crate) async fn deep_sleep_task(resources: SleepResources) {
+    let mut rtc = Rtc::new(resources.lpwr);
+    let (sleep_seconds, reason) = sleep_request().await;
     log::info!("Received sleep request for {sleep_seconds} seconds. reason: {reason:?}");
     let timer = TimerWakeupSource::new(Duration::from_secs(sleep_seconds));
-    rtc.sleep_deep(&[&timer]);
+
+    // The buttons pull to ground, so keep them pulled up through deep sleep
+    let mut next_button = resources.next_button;
+    let mut previous_button = resources.previous_button;
+    next_button.rtcio_pullup(true);
+    previous_button.rtcio_pullup(true);
+    let next = Ext0WakeupSource::new(next_button, WakeupLevel::Low);
+    let mut previous_pins: [&mut dyn RtcPin; 1] = [&mut previous_button];
+    let previous = Ext1WakeupSource::new(&mut previous_pins, WakeupLevel::Low);
+
+    rtc.sleep_deep(&[&timer, &next, &previous]);
+}
+
+/// Wait for a sleep request, holding off the others while a console session is open until
+/// the session asks for sleep itself or goes idle
+async fn sleep_request() -> (u64, SleepReason) {
+    let mut deferred = None;
+    loop {
+        let (sleep_seconds, reason) = SLEEP_REQUEST.wait().await;
+        match reason {
+            SleepReason::Console => return (sleep_seconds, reason),
+            SleepReason::ConsoleIdle => {
+                // Without a held off request the device is still busy and will ask later
+                if let Some(request) = deferred.take() {
+                    return request;
+                }
+            }
+            _ if CONSOLE_ACTIVE.load(Ordering::Relaxed) => {
+                log::info!("Console session open, holding off sleep for {sleep_seconds} seconds");
+                deferred = Some((sleep_seconds, reason));
+            }
+            _ => return (sleep_seconds, reason),
+        }
+    }
 }
@@ -1,31 +1,46 @@
 use embassy_net::Stack;
 use embassy_time::{Duration, Timer};
 
-use core::fmt::Write;
-use heapless::String;
 use crate::{
     DATA_CHANNEL, NETWORK_ERROR, NETWORK_READY,
-    config::SLEEP_ON_ERROR_SECS,
-    weather::api::fetch_weather,
+    config::{MULTI_LOCATION_SUMMARY, SLEEP_ON_ERROR_SECS},
+    error::{AppError, Result, record_error},
+    location::{resolve_location, resolve_location_offline, resolve_locations},
+    weather::{
+        alerts::fetch_alerts,
+        api::{fetch_air_quality, fetch_location_summary, fetch_weather},
+        cache::{load_forecast, load_summary},
+        model::{MAX_SUMMARY_LOCATIONS, SummaryReport, WeatherReport, WeatherScreen},
+    },
 };
+use core::fmt::Write;
+use heapless::String;
 
 #[embassy_executor::task]
 pub(crate) async fn weather_fetcher_task(stack: Stack<'static>) {
-    NETWORK_READY.wait().await;
+    if let Err(e) = NETWORK_READY.wait().await {
+        show_fetch_failure(e).await;
+        return;
+    }
 
     const MAX_ATTEMPTS: usize = 3;
     for attempt in 0..MAX_ATTEMPTS {
-        match fetch_weather(stack).await {
-            Ok(weather_data) => {
-                DATA_CHANNEL.send(weather_data).await;
+        let result = if MULTI_LOCATION_SUMMARY {
+            fetch_summary_screen(stack).await
+        } else {
+            fetch_station_screen(stack).await
+        };
+        match result {
+            Ok(screen) => {
+                DATA_CHANNEL.send(screen).await;
                 return;
             }
             Err(e) => {
                 log::error!("Failed to fetch weather (attempt {}): {:?}", attempt + 1, e);
-                if attempt + 1 >= MAX_ATTEMPTS {
-                    let mut err_msg: String<128> = String::new();
-                    let _ = write!(err_msg, "Failed to fetch weather: {:?}", e);
-                    NETWORK_ERROR.signal(err_msg);
+                record_error(&e);
+                // A rejected request is a configuration mistake that retrying will not fix
+                if matches!(e, AppError::ApiError(_)) || attempt + 1 >= MAX_ATTEMPTS {
+                    show_fetch_failure(e).await;
                     return;
                 }
                 Timer::after(Duration::from_secs(SLEEP_ON_ERROR_SECS)).await;
@@ -33,3 +48,98 @@ pub(crate) async fn weather_fetcher_task(stack: Stack<'static>) {
         }
     }
 }
+
+/// Show the last saved screen marked with `error`, or the error itself when nothing was saved.
+///
+/// An outage should not wipe a forecast that is still useful, but a rejected request is shown
+/// as is so the configuration mistake behind it is noticed.
+pub(crate) async fn show_fetch_failure(error: AppError) {
+    let rejected = matches!(error, AppError::ApiError(_));
+    if !rejected {
+        let saved = if MULTI_LOCATION_SUMMARY {
+            saved_summary_screen(error.clone()).await
+        } else {
+            saved_station_screen(error.clone()).await
+        };
+        if let Some(screen) = saved {
+            DATA_CHANNEL.send(screen).await;
+            return;
+        }
+    }
+    let mut err_msg: String<128> = String::new();
+    let _ = if rejected {
+        write!(err_msg, "{}", error)
+    } else {
+        write!(err_msg, "Failed to fetch weather: {:?}", error)
+    };
+    NETWORK_ERROR.signal(err_msg);
+}
+
+/// Fetch the full forecast and air quality for the active location
+async fn fetch_station_screen(stack: Stack<'static>) -> Result<WeatherScreen> {
+    let location = resolve_location(stack).await?;
+    let weather_data = fetch_weather(stack, &location).await?;
+
+    // Air quality and alerts are supplementary, so a failure here must not block the forecast
+    let air_quality = match fetch_air_quality(stack, &location).await {
+        Ok(air_quality) => Some(air_quality),
+        Err(e) => {
+            log::warn!("Failed to fetch air quality: {:?}", e);
+            None
+        }
+    };
+    let alerts = match fetch_alerts(stack, &location).await {
+        Ok(alerts) => Some(alerts),
+        Err(e) => {
+            log::warn!("Failed to fetch weather alerts: {:?}", e);
+            None
+        }
+    };
+    Ok(WeatherScreen::Station(WeatherReport {
+        location,
+        forecast: weather_data,
+        air_quality,
+        alerts,
+        fetch_error: None,
+    }))
+}
+
+/// The last saved forecast for the active location, marked with the `error` that kept it from
+/// being refreshed
+async fn saved_station_screen(error: AppError) -> Option<WeatherScreen> {
+    let location = resolve_location_offline().await?;
+    let (location, forecast) = load_forecast(&location).await?;
+    log::warn!(
+        "Showing the forecast saved at {} instead",
+        forecast.current.time
+    );
+    Some(WeatherScreen::Station(WeatherReport {
+        location,
+        forecast,
+        air_quality: None,
+        alerts: None,
+        fetch_error: Some(error),
+    }))
+}
+
+/// Fetch a compact forecast for all saved locations in one request
+async fn fetch_summary_screen(stack: Stack<'static>) -> Result<WeatherScreen> {
+    let locations = resolve_locations::<MAX_SUMMARY_LOCATIONS>(stack).await?;
+    let summary = fetch_location_summary(stack, &locations).await?;
+    Ok(WeatherScreen::Summary(SummaryReport {
+        locations,
+        summary,
+        fetch_error: None,
+    }))
+}
+
+/// The last saved summary, marked with the `error` that kept it from being refreshed
+async fn saved_summary_screen(error: AppError) -> Option<WeatherScreen> {
+    let (locations, summary) = load_summary().await?;
+    log::warn!("Showing the saved location summary instead");
+    Some(WeatherScreen::Summary(SummaryReport {
+        locations,
+        summary,
+        fetch_error: Some(error),
+    }))
+}
@@ -1,4 +1,4 @@
-use time::{Month, OffsetDateTime, Weekday};
+use time::{Month, OffsetDateTime, Weekday, format_description::well_known::Rfc3339};
 
 /// Formats a Unix timestamp as a human-readable date, adjusted for a UTC offset.
 ///
@@ -50,6 +50,57 @@ pub fn format_date_unix(ts: i64, utc_offset_seconds: i32) -> Option<heapless::St
     Some(out)
 }
 
+/// Formats a Unix timestamp as a short date such as `"Wed, Jan 1st"`, adjusted for a UTC
+/// offset.
+///
+/// Returns `None` if the timestamp is out of range.
+pub fn format_short_date_unix(ts: i64, utc_offset_seconds: i32) -> Option<heapless::String<16>> {
+    let local_ts = ts + utc_offset_seconds as i64;
+    let dt = OffsetDateTime::from_unix_timestamp(local_ts).ok()?;
+
+    let weekday = match dt.weekday() {
+        Weekday::Monday => "Mon",
+        Weekday::Tuesday => "Tue",
+        Weekday::Wednesday => "Wed",
+        Weekday::Thursday => "Thu",
+        Weekday::Friday => "Fri",
+        Weekday::Saturday => "Sat",
+        Weekday::Sunday => "Sun",
+    };
+
+    let month_name = match dt.month() {
+        Month::January => "Jan",
+        Month::February => "Feb",
+        Month::March => "Mar",
+        Month::April => "Apr",
+        Month::May => "May",
+        Month::June => "Jun",
+        Month::July => "Jul",
+        Month::August => "Aug",
+        Month::September => "Sep",
+        Month::October => "Oct",
+        Month::November => "Nov",
+        Month::December => "Dec",
+    };
+
+    let day = dt.day();
+    let mut out = heapless::String::<16>::new();
+    let _ = core::fmt::write(
+        &mut out,
+        format_args!("{}, {} {}{}", weekday, month_name, day, ordinal(day)),
+    );
+    Some(out)
+}
+
+/// Parses an RFC 3339 timestamp such as `"2025-01-01T18:00:00-07:00"` into a Unix timestamp.
+///
+/// Returns `None` if the text is not a valid RFC 3339 date and time.
+pub fn parse_rfc3339(text: &str) -> Option<i64> {
+    OffsetDateTime::parse(text, &Rfc3339)
+        .ok()
+        .map(OffsetDateTime::unix_timestamp)
+}
+
 /// Formats a Unix timestamp as `HH:MM` in local time.
 ///
 /// `utc_offset_seconds` is added to `ts` before formatting. Returns `None` if the
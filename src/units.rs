@@ -0,0 +1,274 @@
+//! Temperature, speed, length and pressure values with their units, and conversions between
+//! the units Open-Meteo reports and the display units
+
+use serde::{Deserialize, Serialize};
+
+/// Temperature scale
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+/// Wind speed unit
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeedUnit {
+    KilometresPerHour,
+    MetresPerSecond,
+    MilesPerHour,
+    Knots,
+}
+
+/// Unit of precipitation and snowfall amounts
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthUnit {
+    Millimetre,
+    Centimetre,
+    Inch,
+}
+
+/// Atmospheric pressure unit
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureUnit {
+    Hectopascal,
+    InchOfMercury,
+}
+
+impl TemperatureUnit {
+    /// Parse a unit as reported in Open-Meteo `*_units` ("°F") or as requested ("fahrenheit")
+    pub fn parse(unit: &str) -> Option<Self> {
+        match unit {
+            "°C" | "celsius" => Some(TemperatureUnit::Celsius),
+            "°F" | "fahrenheit" => Some(TemperatureUnit::Fahrenheit),
+            _ => None,
+        }
+    }
+
+    /// Label shown on the display, which has no degree sign
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "C",
+            TemperatureUnit::Fahrenheit => "F",
+        }
+    }
+}
+
+impl SpeedUnit {
+    /// Parse a unit as reported in Open-Meteo `*_units` ("mp/h") or as requested ("mph")
+    pub fn parse(unit: &str) -> Option<Self> {
+        match unit {
+            "km/h" | "kmh" => Some(SpeedUnit::KilometresPerHour),
+            "m/s" | "ms" => Some(SpeedUnit::MetresPerSecond),
+            "mp/h" | "mph" => Some(SpeedUnit::MilesPerHour),
+            "kn" => Some(SpeedUnit::Knots),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            SpeedUnit::KilometresPerHour => "km/h",
+            SpeedUnit::MetresPerSecond => "m/s",
+            SpeedUnit::MilesPerHour => "mph",
+            SpeedUnit::Knots => "kn",
+        }
+    }
+
+    fn metres_per_second(self) -> f32 {
+        match self {
+            SpeedUnit::KilometresPerHour => 1.0 / 3.6,
+            SpeedUnit::MetresPerSecond => 1.0,
+            SpeedUnit::MilesPerHour => 0.447_04,
+            SpeedUnit::Knots => 0.514_444,
+        }
+    }
+}
+
+impl LengthUnit {
+    /// Parse a unit as reported in Open-Meteo `*_units` or as requested ("mm", "cm" or "inch")
+    pub fn parse(unit: &str) -> Option<Self> {
+        match unit {
+            "mm" => Some(LengthUnit::Millimetre),
+            "cm" => Some(LengthUnit::Centimetre),
+            "inch" => Some(LengthUnit::Inch),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            LengthUnit::Millimetre => "mm",
+            LengthUnit::Centimetre => "cm",
+            LengthUnit::Inch => "in",
+        }
+    }
+
+    fn millimetres(self) -> f32 {
+        match self {
+            LengthUnit::Millimetre => 1.0,
+            LengthUnit::Centimetre => 10.0,
+            LengthUnit::Inch => 25.4,
+        }
+    }
+}
+
+impl PressureUnit {
+    /// Parse a unit as reported in Open-Meteo `*_units` ("hPa") or "inHg"
+    pub fn parse(unit: &str) -> Option<Self> {
+        match unit {
+            "hPa" => Some(PressureUnit::Hectopascal),
+            "inHg" => Some(PressureUnit::InchOfMercury),
+            _ => None,
+        }
+    }
+
+    /// Label shown on the display, "in" for inHg to fit the forecast row
+    pub fn symbol(self) -> &'static str {
+        match self {
+            PressureUnit::Hectopascal => "hPa",
+            PressureUnit::InchOfMercury => "in",
+        }
+    }
+
+    fn hectopascals(self) -> f32 {
+        match self {
+            PressureUnit::Hectopascal => 1.0,
+            PressureUnit::InchOfMercury => 33.863_9,
+        }
+    }
+}
+
+/// A temperature with its scale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Temperature {
+    pub value: f32,
+    pub unit: TemperatureUnit,
+}
+
+/// A speed with its unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Speed {
+    pub value: f32,
+    pub unit: SpeedUnit,
+}
+
+/// A precipitation or snowfall amount with its unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Length {
+    pub value: f32,
+    pub unit: LengthUnit,
+}
+
+/// A pressure with its unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pressure {
+    pub value: f32,
+    pub unit: PressureUnit,
+}
+
+impl Temperature {
+    pub fn new(value: f32, unit: TemperatureUnit) -> Self {
+        Temperature { value, unit }
+    }
+
+    /// The same temperature in `unit`
+    pub fn to(self, unit: TemperatureUnit) -> Self {
+        let value = match (self.unit, unit) {
+            (TemperatureUnit::Celsius, TemperatureUnit::Fahrenheit) => self.value * 1.8 + 32.0,
+            (TemperatureUnit::Fahrenheit, TemperatureUnit::Celsius) => (self.value - 32.0) / 1.8,
+            _ => self.value,
+        };
+        Temperature { value, unit }
+    }
+}
+
+impl Speed {
+    pub fn new(value: f32, unit: SpeedUnit) -> Self {
+        Speed { value, unit }
+    }
+
+    /// The same speed in `unit`
+    pub fn to(self, unit: SpeedUnit) -> Self {
+        if self.unit == unit {
+            return self;
+        }
+        let value = self.value * self.unit.metres_per_second() / unit.metres_per_second();
+        Speed { value, unit }
+    }
+}
+
+impl Length {
+    pub fn new(value: f32, unit: LengthUnit) -> Self {
+        Length { value, unit }
+    }
+
+    /// The same length in `unit`
+    pub fn to(self, unit: LengthUnit) -> Self {
+        if self.unit == unit {
+            return self;
+        }
+        let value = self.value * self.unit.millimetres() / unit.millimetres();
+        Length { value, unit }
+    }
+}
+
+impl Pressure {
+    pub fn new(value: f32, unit: PressureUnit) -> Self {
+        Pressure { value, unit }
+    }
+
+    /// The same pressure in `unit`
+    pub fn to(self, unit: PressureUnit) -> Self {
+        if self.unit == unit {
+            return self;
+        }
+        let value = self.value * self.unit.hectopascals() / unit.hectopascals();
+        Pressure { value, unit }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_open_meteo_and_request_units() {
+        assert_eq!(
+            TemperatureUnit::parse("°F"),
+            Some(TemperatureUnit::Fahrenheit)
+        );
+        assert_eq!(
+            TemperatureUnit::parse("celsius"),
+            Some(TemperatureUnit::Celsius)
+        );
+        assert_eq!(SpeedUnit::parse("mp/h"), Some(SpeedUnit::MilesPerHour));
+        assert_eq!(SpeedUnit::parse("mph"), Some(SpeedUnit::MilesPerHour));
+        assert_eq!(LengthUnit::parse("inch"), Some(LengthUnit::Inch));
+        assert_eq!(PressureUnit::parse("hPa"), Some(PressureUnit::Hectopascal));
+        assert_eq!(TemperatureUnit::parse("K"), None);
+        assert_eq!(LengthUnit::parse("inches"), None);
+    }
+
+    #[test]
+    fn converts_between_units() {
+        let boiling =
+            Temperature::new(100.0, TemperatureUnit::Celsius).to(TemperatureUnit::Fahrenheit);
+        assert!((boiling.value - 212.0).abs() < 1e-3);
+        assert_eq!(boiling.unit, TemperatureUnit::Fahrenheit);
+        let cold =
+            Temperature::new(-40.0, TemperatureUnit::Fahrenheit).to(TemperatureUnit::Celsius);
+        assert!((cold.value + 40.0).abs() < 1e-3);
+
+        let wind = Speed::new(36.0, SpeedUnit::KilometresPerHour).to(SpeedUnit::MetresPerSecond);
+        assert!((wind.value - 10.0).abs() < 1e-3);
+        let wind = Speed::new(10.0, SpeedUnit::MilesPerHour).to(SpeedUnit::Knots);
+        assert!((wind.value - 8.690).abs() < 1e-3);
+
+        let rain = Length::new(2.54, LengthUnit::Centimetre).to(LengthUnit::Inch);
+        assert!((rain.value - 1.0).abs() < 1e-4);
+
+        let pressure =
+            Pressure::new(1013.25, PressureUnit::Hectopascal).to(PressureUnit::InchOfMercury);
+        assert!((pressure.value - 29.92).abs() < 0.01);
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// Where severe weather alerts come from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertSource {
+    /// No alerts are fetched
+    Disabled,
+    /// US National Weather Service alerts for the forecast point
+    Nws,
+    /// MeteoAlarm CAP warnings for `METEOALARM_COUNTRY` whose area covers the forecast point
+    MeteoAlarm,
+}
+
+impl AlertSource {
+    /// Parse the name shown on the serial console
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "disabled" => Some(AlertSource::Disabled),
+            "nws" => Some(AlertSource::Nws),
+            "meteoalarm" => Some(AlertSource::MeteoAlarm),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AlertSource::Disabled => "disabled",
+            AlertSource::Nws => "nws",
+            AlertSource::MeteoAlarm => "meteoalarm",
+        }
+    }
+}
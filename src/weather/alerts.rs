@@ -0,0 +1,396 @@
+use core::{fmt, marker::PhantomData};
+
+use heapless::{String, Vec};
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+};
+use serde_json_core::{self as json_core};
+
+use crate::{
+    config::{
+        METEOALARM_COUNTRY, METEOALARM_HOST, METEOALARM_REGION, NWS_ALERTS_HOST, NWS_USER_AGENT,
+    },
+    error::{AppError, Result},
+    location::Location,
+    network::http::{extract_body, http_get_raw, url_encode_component},
+    settings,
+    time::parse_rfc3339,
+    weather::{alert_source::AlertSource, model::deserialize_truncated_str},
+};
+
+// Heapless sizing limits
+pub const MAX_ALERTS: usize = 8;
+const MAX_CAP_INFOS: usize = 4;
+const MAX_CAP_AREAS: usize = 8;
+const MAX_CAP_POLYGONS: usize = 4;
+
+// Heuristic string capacities
+const EVENT_LEN: usize = 40;
+const AREA_LEN: usize = 48;
+const BUF_LEN: usize = 32;
+
+/// CAP severity, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertSeverity {
+    Unknown,
+    Minor,
+    Moderate,
+    Severe,
+    Extreme,
+}
+
+impl AlertSeverity {
+    fn from_cap(severity: Option<&str>) -> Self {
+        match severity {
+            Some(s) if s.eq_ignore_ascii_case("extreme") => AlertSeverity::Extreme,
+            Some(s) if s.eq_ignore_ascii_case("severe") => AlertSeverity::Severe,
+            Some(s) if s.eq_ignore_ascii_case("moderate") => AlertSeverity::Moderate,
+            Some(s) if s.eq_ignore_ascii_case("minor") => AlertSeverity::Minor,
+            _ => AlertSeverity::Unknown,
+        }
+    }
+}
+
+/// A single active weather alert
+#[derive(Debug, Clone)]
+pub struct WeatherAlert {
+    /// Event name, e.g. "Winter Storm Warning"
+    pub event: String<EVENT_LEN>,
+    pub severity: AlertSeverity,
+    /// Unix time the alert ends, if given
+    pub expires: Option<i64>,
+}
+
+/// Active alerts for the forecast location
+#[derive(Debug, Default)]
+pub struct WeatherAlerts {
+    pub alerts: Vec<WeatherAlert, MAX_ALERTS>,
+}
+
+impl WeatherAlerts {
+    /// Most severe alert that has not expired at unix time `now`
+    pub fn most_severe(&self, now: i64) -> Option<&WeatherAlert> {
+        self.alerts
+            .iter()
+            .filter(|alert| alert.expires.is_none_or(|expires| expires > now))
+            .max_by_key(|alert| alert.severity)
+    }
+}
+
+/// NWS `/alerts/active` GeoJSON response
+#[derive(Deserialize, Debug)]
+struct NwsAlertCollection {
+    #[serde(deserialize_with = "deserialize_first")]
+    features: Vec<NwsFeature, MAX_ALERTS>,
+}
+
+#[derive(Deserialize, Debug)]
+struct NwsFeature {
+    properties: NwsProperties,
+}
+
+#[derive(Deserialize, Debug)]
+struct NwsProperties {
+    #[serde(deserialize_with = "deserialize_truncated_str")]
+    event: String<EVENT_LEN>,
+    severity: Option<String<BUF_LEN>>,
+    expires: Option<String<BUF_LEN>>,
+    /// End of the hazard itself, `null` when unknown
+    ends: Option<String<BUF_LEN>>,
+}
+
+/// Warning of a MeteoAlarm country feed
+#[derive(Deserialize, Debug)]
+struct MeteoAlarmWarning<'a> {
+    #[serde(borrow)]
+    alert: CapAlert<'a>,
+}
+
+#[derive(Deserialize, Debug)]
+struct CapAlert<'a> {
+    /// One entry per language
+    #[serde(borrow, deserialize_with = "deserialize_first")]
+    info: Vec<CapInfo<'a>, MAX_CAP_INFOS>,
+}
+
+#[derive(Deserialize, Debug)]
+struct CapInfo<'a> {
+    language: Option<String<BUF_LEN>>,
+    #[serde(deserialize_with = "deserialize_truncated_str")]
+    event: String<EVENT_LEN>,
+    severity: Option<String<BUF_LEN>>,
+    expires: Option<String<BUF_LEN>>,
+    #[serde(default, borrow, deserialize_with = "deserialize_first")]
+    area: Vec<CapArea<'a>, MAX_CAP_AREAS>,
+}
+
+#[derive(Deserialize, Debug)]
+struct CapArea<'a> {
+    #[serde(rename = "areaDesc", deserialize_with = "deserialize_truncated_str")]
+    area_desc: String<AREA_LEN>,
+    /// CAP polygons, borrowed from the response since they can be long
+    #[serde(default, borrow, deserialize_with = "deserialize_first")]
+    polygon: Vec<&'a str, MAX_CAP_POLYGONS>,
+}
+
+impl CapArea<'_> {
+    /// Whether the area covers `point`, judged by its polygons when it has any and by
+    /// `METEOALARM_REGION` otherwise
+    fn covers(&self, point: (f32, f32)) -> bool {
+        if self.polygon.is_empty() {
+            return self.area_desc.eq_ignore_ascii_case(METEOALARM_REGION);
+        }
+        self.polygon
+            .iter()
+            .any(|polygon| polygon_contains(polygon, point))
+    }
+}
+
+impl MeteoAlarmWarning<'_> {
+    fn covers(&self, point: (f32, f32)) -> bool {
+        self.alert
+            .info
+            .iter()
+            .any(|info| info.area.iter().any(|area| area.covers(point)))
+    }
+
+    /// English text when available, otherwise the first language
+    fn info(&self) -> Option<&CapInfo<'_>> {
+        self.alert
+            .info
+            .iter()
+            .find(|info| {
+                info.language
+                    .as_deref()
+                    .is_some_and(|language| language.starts_with("en"))
+            })
+            .or(self.alert.info.first())
+    }
+}
+
+/// Parse the NWS alerts GeoJSON response into WeatherAlerts
+fn parse_nws_alerts(body: &[u8]) -> core::result::Result<WeatherAlerts, json_core::de::Error> {
+    let (collection, _consumed) = json_core::from_slice::<NwsAlertCollection>(body)?;
+    let mut alerts = WeatherAlerts::default();
+    for feature in collection.features {
+        let properties = feature.properties;
+        let expires = properties.ends.or(properties.expires);
+        let _ = alerts.alerts.push(WeatherAlert {
+            event: properties.event,
+            severity: AlertSeverity::from_cap(properties.severity.as_deref()),
+            expires: expires.as_deref().and_then(parse_rfc3339),
+        });
+    }
+    Ok(alerts)
+}
+
+/// Parse a MeteoAlarm country feed into WeatherAlerts for the warnings covering `point`
+fn parse_meteoalarm_alerts(
+    body: &[u8],
+    point: (f32, f32),
+) -> core::result::Result<WeatherAlerts, json_core::de::Error> {
+    let mut deserializer = json_core::de::Deserializer::new(body, None);
+    let warnings = CoveringWarnings(point).deserialize(&mut deserializer)?;
+    deserializer.end()?;
+
+    let mut alerts = WeatherAlerts::default();
+    for warning in &warnings {
+        if let Some(info) = warning.info() {
+            let _ = alerts.alerts.push(WeatherAlert {
+                event: info.event.clone(),
+                severity: AlertSeverity::from_cap(info.severity.as_deref()),
+                expires: info.expires.as_deref().and_then(parse_rfc3339),
+            });
+        }
+    }
+    Ok(alerts)
+}
+
+/// Whether the CAP `polygon` ("lat,lon lat,lon ...", first and last point equal) contains
+/// `point`, by counting the edges a ray from the point crosses
+fn polygon_contains(polygon: &str, (latitude, longitude): (f32, f32)) -> bool {
+    let mut vertices = polygon.split_ascii_whitespace().filter_map(|pair| {
+        let (latitude, longitude) = pair.split_once(',')?;
+        Some((
+            latitude.parse::<f32>().ok()?,
+            longitude.parse::<f32>().ok()?,
+        ))
+    });
+    let Some(mut previous) = vertices.next() else {
+        return false;
+    };
+    let mut inside = false;
+    for vertex in vertices {
+        if (vertex.0 > latitude) != (previous.0 > latitude)
+            && longitude
+                < (previous.1 - vertex.1) * (latitude - vertex.0) / (previous.0 - vertex.0)
+                    + vertex.1
+        {
+            inside = !inside;
+        }
+        previous = vertex;
+    }
+    inside
+}
+
+/// Fetch the active weather alerts for `location` from the alert source in the settings.
+///
+/// Returns the parsed `WeatherAlerts`, empty when alerts are disabled, or an error `Result`
+/// on failure.
+pub async fn fetch_alerts(
+    stack: embassy_net::Stack<'static>,
+    location: &Location,
+) -> Result<WeatherAlerts> {
+    let source = settings::get().alert_source;
+    let (host, query, headers) = match source {
+        AlertSource::Disabled => return Ok(WeatherAlerts::default()),
+        AlertSource::Nws => (
+            NWS_ALERTS_HOST,
+            format!(
+                "/alerts/active?point={},{}",
+                url_encode_component(&location.latitude)?,
+                url_encode_component(&location.longitude)?
+            ),
+            // NWS rejects requests without a User-Agent
+            format!(
+                "User-Agent: {}\r\nAccept: application/geo+json",
+                NWS_USER_AGENT
+            ),
+        ),
+        AlertSource::MeteoAlarm => (
+            METEOALARM_HOST,
+            format!(
+                "/api/v1/warnings/feeds-{}",
+                url_encode_component(METEOALARM_COUNTRY)?
+            ),
+            "Accept: application/json".into(),
+        ),
+    };
+
+    let buf = http_get_raw(stack, host, &query, Some(&headers))
+        .await
+        .map_err(|e| {
+            log::error!("Fetching weather alerts failed: {:?}", e);
+            e
+        })?;
+
+    let body = extract_body(&buf);
+    let parsed = match source {
+        AlertSource::MeteoAlarm => {
+            let point = (
+                location.latitude.parse().unwrap_or_default(),
+                location.longitude.parse().unwrap_or_default(),
+            );
+            parse_meteoalarm_alerts(body, point)
+        }
+        _ => parse_nws_alerts(body),
+    }
+    .map_err(|e| {
+        log::error!("Failed to parse weather alerts JSON response: {:?}", e);
+        AppError::from(e)
+    })?;
+
+    log::debug!("{parsed:?}");
+
+    Ok(parsed)
+}
+
+/// Deserialize a JSON array keeping the first `N` elements and skipping the rest
+fn deserialize_first<'de, D, T, const N: usize>(
+    deserializer: D,
+) -> core::result::Result<Vec<T, N>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    deserializer.deserialize_seq(FilteredSeqVisitor(|_: &T| true, PhantomData))
+}
+
+/// Deserializes a MeteoAlarm country feed, keeping the warnings that cover the point given as
+/// (latitude, longitude)
+struct CoveringWarnings((f32, f32));
+
+impl<'de> DeserializeSeed<'de> for CoveringWarnings {
+    type Value = Vec<MeteoAlarmWarning<'de>, MAX_ALERTS>;
+
+    fn deserialize<D>(self, deserializer: D) -> core::result::Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for CoveringWarnings {
+    type Value = Vec<MeteoAlarmWarning<'de>, MAX_ALERTS>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a MeteoAlarm feed")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> core::result::Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let point = self.0;
+        let mut warnings = Vec::new();
+        while let Some(key) = map.next_key::<&str>()? {
+            if key == "warnings" {
+                let covers = |warning: &MeteoAlarmWarning| warning.covers(point);
+                warnings = map.next_value_seed(FilteredSeqVisitor(covers, PhantomData))?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(warnings)
+    }
+}
+
+/// Collects the array elements accepted by the filter until `N` are kept
+struct FilteredSeqVisitor<F, T, const N: usize>(F, PhantomData<T>);
+
+impl<'de, F, T, const N: usize> DeserializeSeed<'de> for FilteredSeqVisitor<F, T, N>
+where
+    F: Fn(&T) -> bool,
+    T: Deserialize<'de>,
+{
+    type Value = Vec<T, N>;
+
+    fn deserialize<D>(self, deserializer: D) -> core::result::Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F, T, const N: usize> Visitor<'de> for FilteredSeqVisitor<F, T, N>
+where
+    F: Fn(&T) -> bool,
+    T: Deserialize<'de>,
+{
+    type Value = Vec<T, N>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> core::result::Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::new();
+        while !values.is_full() {
+            match seq.next_element::<T>()? {
+                Some(value) if (self.0)(&value) => {
+                    let _ = values.push(value);
+                }
+                Some(_) => (),
+                None => return Ok(values),
+            }
+        }
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(values)
+    }
+}
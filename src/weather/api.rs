@@ -2,32 +2,89 @@ use core::fmt::Write as _;
 
 use crate::{
     config::{
-        OPENMETEO_LATITUDE, OPENMETEO_LONGITUDE, OPENMETEO_TEMP_UNIT, OPENMETEO_TIMEZONE,
-        OPENMETEO_WIND_UNIT,
+        OPENMETEO_FORECAST_DAYS, OPENMETEO_FORECAST_HOURS, OPENMETEO_PRECIP_UNIT,
+        OPENMETEO_TEMP_UNIT, OPENMETEO_WIND_UNIT,
     },
     error::{AppError, Result},
+    location::Location,
     network::http::{extract_body, http_get_raw, url_encode_component},
-    weather::model::OpenMeteoResponse,
+    settings,
+    weather::{
+        cache::{save_forecast, save_summary},
+        model::{
+            AirQualityResponse, ApiErrorResponse, BatchSummaryResponse, GeocodingResponse,
+            MAX_FORECAST_DAYS, MAX_GEOCODING_RESULTS, MAX_HOURS, MAX_SUMMARY_LOCATIONS,
+            OpenMeteoResponse, PAST_HOURS, TODAY,
+        },
+        validate::ValidatedForecast,
+    },
 };
 
 use alloc::{string::String, vec::Vec};
 
-const DAILY_FIELDS: &str = "weather_code,temperature_2m_max,temperature_2m_min,sunrise,sunset,wind_speed_10m_max,wind_gusts_10m_max,wind_direction_10m_dominant";
-const CURRENT_FIELDS: &str = "temperature_2m";
+const DAILY_FIELDS: &str = "weather_code,temperature_2m_max,temperature_2m_min,sunrise,sunset,wind_speed_10m_max,wind_gusts_10m_max,wind_direction_10m_dominant,precipitation_sum,precipitation_probability_max,rain_sum,snowfall_sum,precipitation_hours,uv_index_max,sunshine_duration";
+const HOURLY_FIELDS: &str = "temperature_2m,precipitation_probability,weather_code,wind_speed_10m,wind_direction_10m,pressure_msl";
+const CURRENT_FIELDS: &str = "temperature_2m,apparent_temperature,relative_humidity_2m,weather_code,is_day,wind_speed_10m,wind_direction_10m,surface_pressure";
+const SUMMARY_DAILY_FIELDS: &str =
+    "weather_code,temperature_2m_max,temperature_2m_min,precipitation_probability_max";
+const AIR_QUALITY_FIELDS: &str = "us_aqi,european_aqi,pm2_5,alder_pollen,birch_pollen,grass_pollen,mugwort_pollen,olive_pollen,ragweed_pollen";
 const HEADERS_STR: &str = "Accept: application/json";
 pub const OPEN_METEO_URL: &str = "api.open-meteo.com";
+pub const OPEN_METEO_AIR_QUALITY_URL: &str = "air-quality-api.open-meteo.com";
+pub const OPEN_METEO_GEOCODING_URL: &str = "geocoding-api.open-meteo.com";
+
+// The hourly vectors in the model are sized for at most `MAX_HOURS` entries, which the default
+// settings must fit
+const _: () = assert!(OPENMETEO_FORECAST_HOURS >= 1 && OPENMETEO_FORECAST_HOURS <= MAX_HOURS);
+// The daily vectors hold yesterday and at most `MAX_FORECAST_DAYS` days from today
+const _: () = assert!(OPENMETEO_FORECAST_DAYS >= 1 && OPENMETEO_FORECAST_DAYS <= MAX_FORECAST_DAYS);
+
+/// Variables and range requested from the forecast endpoint
+struct QueryFields {
+    daily: &'static str,
+    /// Hourly variables, fetched for `PAST_HOURS` before and the configured number of hours
+    /// from the current hour
+    hourly: Option<&'static str>,
+    current: &'static str,
+    /// Number of forecast days, the configured setting when `None`
+    forecast_days: Option<usize>,
+    /// Number of past days put before today in the daily data
+    past_days: Option<usize>,
+}
 
-/// Fetch weather from Open-Meteo using the provided network `stack`.
+/// Full forecast for the weather station view
+const FORECAST_QUERY: QueryFields = QueryFields {
+    daily: DAILY_FIELDS,
+    hourly: Some(HOURLY_FIELDS),
+    current: CURRENT_FIELDS,
+    forecast_days: None,
+    // Yesterday, to compare today against
+    past_days: Some(TODAY),
+};
+
+/// Current conditions and today's outlook for the multi-location summary
+const SUMMARY_QUERY: QueryFields = QueryFields {
+    daily: SUMMARY_DAILY_FIELDS,
+    hourly: None,
+    current: CURRENT_FIELDS,
+    forecast_days: Some(1),
+    past_days: None,
+};
+
+/// Fetch weather for `location` from Open-Meteo using the provided network `stack`.
 ///
-/// Returns a parsed `OpenMeteoResponse` on success or an error `Result` on failure.
-pub async fn fetch_weather(stack: embassy_net::Stack<'static>) -> Result<OpenMeteoResponse> {
+/// Returns the parsed and validated forecast on success or an error `Result` on failure.
+pub async fn fetch_weather(
+    stack: embassy_net::Stack<'static>,
+    location: &Location,
+) -> Result<ValidatedForecast> {
     let buf = fetch_weather_data(
         stack,
-        OPENMETEO_LATITUDE,
-        OPENMETEO_LONGITUDE,
-        OPENMETEO_TIMEZONE,
+        core::slice::from_ref(location),
+        &FORECAST_QUERY,
         OPENMETEO_TEMP_UNIT,
         OPENMETEO_WIND_UNIT,
+        OPENMETEO_PRECIP_UNIT,
     )
     .await
     .map_err(|e| {
@@ -35,58 +92,237 @@ pub async fn fetch_weather(stack: embassy_net::Stack<'static>) -> Result<OpenMet
         e
     })?;
 
-    let parsed = OpenMeteoResponse::try_from(extract_body(&buf)).map_err(|e| {
+    let body = extract_body(&buf);
+    let parsed = OpenMeteoResponse::try_from(body).map_err(|e| {
         log::error!("Failed to parse JSON response: {:?}", e);
+        api_error(body).unwrap_or(AppError::from(e))
+    })?;
+
+    log::debug!("{parsed:?}");
+
+    let validated = ValidatedForecast::try_from(parsed).map_err(|e| {
+        log::error!("Rejected forecast: {}", e);
         AppError::from(e)
     })?;
 
+    save_forecast(location, body).await;
+
+    Ok(validated)
+}
+
+/// Fetch a compact forecast for several `locations` in a single Open-Meteo request.
+///
+/// Returns a parsed `BatchSummaryResponse` with one entry per location, in the same order, on
+/// success or an error `Result` on failure.
+pub async fn fetch_location_summary(
+    stack: embassy_net::Stack<'static>,
+    locations: &[Location],
+) -> Result<BatchSummaryResponse> {
+    let locations = &locations[..locations.len().min(MAX_SUMMARY_LOCATIONS)];
+    let buf = fetch_weather_data(
+        stack,
+        locations,
+        &SUMMARY_QUERY,
+        OPENMETEO_TEMP_UNIT,
+        OPENMETEO_WIND_UNIT,
+        OPENMETEO_PRECIP_UNIT,
+    )
+    .await
+    .map_err(|e| {
+        log::error!("Fetching location summary failed: {:?}", e);
+        e
+    })?;
+
+    let body = extract_body(&buf);
+    let parsed = BatchSummaryResponse::try_from(body).map_err(|e| {
+        log::error!("Failed to parse location summary JSON response: {:?}", e);
+        api_error(body).unwrap_or(AppError::from(e))
+    })?;
+
     log::debug!("{parsed:?}");
 
+    if parsed.locations.len() != locations.len() {
+        log::error!(
+            "Location summary returned {} of {} locations",
+            parsed.locations.len(),
+            locations.len()
+        );
+        return Err(AppError::JsonParseFailed);
+    }
+
+    save_summary(locations, body).await;
     Ok(parsed)
 }
 
-/// Fetch weather data for a custom latitude, longitude and timezone.
+/// The reason Open-Meteo gave for rejecting a request, if `body` is an error response
+/// (`{"error": true, "reason": "..."}`) rather than data
+fn api_error(body: &[u8]) -> Option<AppError> {
+    match ApiErrorResponse::try_from(body) {
+        Ok(response) if response.error => {
+            log::error!("Open-Meteo rejected the request: {}", response.reason);
+            Some(AppError::ApiError(response.reason))
+        }
+        _ => None,
+    }
+}
+
+/// Fetch weather data for one or more locations with custom coordinates and timezones.
 async fn fetch_weather_data(
     stack: embassy_net::Stack<'static>,
-    latitude: &str,
-    longitude: &str,
-    timezone: &str,
+    locations: &[Location],
+    fields: &QueryFields,
     temperature_unit: &str,
     windspeed_unit: &str,
+    precipitation_unit: &str,
 ) -> Result<Vec<u8>> {
     // Build request using custom coordinates/timezone
     let query = build_open_meteo_query(
-        latitude,
-        longitude,
-        timezone,
+        locations,
+        fields,
         temperature_unit,
         windspeed_unit,
+        precipitation_unit,
     )?;
 
     // Perform HTTP GET request
     http_get_raw(stack, OPEN_METEO_URL, &query, Some(HEADERS_STR)).await
 }
 
-/// Build an Open-Meteo HTTP request for the given latitude, longitude and timezone.
+/// Build an Open-Meteo HTTP request for the given locations.
+///
+/// Several locations are sent as comma-separated coordinate and timezone lists, which the API
+/// answers with a JSON array holding one response per location.
 fn build_open_meteo_query(
-    latitude: &str,
-    longitude: &str,
-    timezone: &str,
+    locations: &[Location],
+    fields: &QueryFields,
     temperature_unit: &str,
     windspeed_unit: &str,
+    precipitation_unit: &str,
 ) -> Result<String> {
+    let lat_enc = encode_joined(locations, |l| &l.latitude)?;
+    let long_enc = encode_joined(locations, |l| &l.longitude)?;
+    let tz_enc = encode_joined(locations, |l| &l.timezone)?;
+    let temp_unit_enc = url_encode_component(temperature_unit)?;
+    let windspeed_unit_enc = url_encode_component(windspeed_unit)?;
+    let precip_unit_enc = url_encode_component(precipitation_unit)?;
+
+    let settings = settings::get();
+    let mut query: String = String::new();
+    write!(
+        query,
+        "/v1/forecast?latitude={}&longitude={}&daily={}",
+        lat_enc, long_enc, fields.daily
+    )
+    .map_err(|_| AppError::HttpRequestFailed)?;
+    if let Some(hourly) = fields.hourly {
+        write!(
+            query,
+            "&hourly={}&forecast_hours={}&past_hours={}",
+            hourly, settings.forecast_hours, PAST_HOURS
+        )
+        .map_err(|_| AppError::HttpRequestFailed)?;
+    }
+    let forecast_days = fields
+        .forecast_days
+        .unwrap_or(settings.forecast_days as usize);
+    write!(query, "&forecast_days={}", forecast_days).map_err(|_| AppError::HttpRequestFailed)?;
+    if let Some(days) = fields.past_days {
+        write!(query, "&past_days={}", days).map_err(|_| AppError::HttpRequestFailed)?;
+    }
+    write!(
+        query,
+        "&current={}&timezone={}&temperature_unit={}&wind_speed_unit={}&precipitation_unit={}&timeformat=unixtime",
+        fields.current, tz_enc, temp_unit_enc, windspeed_unit_enc, precip_unit_enc
+    )
+    .map_err(|_| AppError::HttpRequestFailed)?;
+    Ok(query)
+}
+
+/// URL-encode one field of every location and join the results with commas.
+fn encode_joined(locations: &[Location], field: impl Fn(&Location) -> &str) -> Result<String> {
+    let mut joined = String::new();
+    for (i, location) in locations.iter().enumerate() {
+        if i > 0 {
+            joined.push(',');
+        }
+        joined.push_str(&url_encode_component(field(location))?);
+    }
+    Ok(joined)
+}
+
+/// Fetch current air quality and pollen for `location` from the Open-Meteo Air Quality API.
+///
+/// Returns a parsed `AirQualityResponse` on success or an error `Result` on failure.
+pub async fn fetch_air_quality(
+    stack: embassy_net::Stack<'static>,
+    location: &Location,
+) -> Result<AirQualityResponse> {
+    let query =
+        build_air_quality_query(&location.latitude, &location.longitude, &location.timezone)?;
+    let buf = http_get_raw(stack, OPEN_METEO_AIR_QUALITY_URL, &query, Some(HEADERS_STR))
+        .await
+        .map_err(|e| {
+            log::error!("Fetching air quality data failed: {:?}", e);
+            e
+        })?;
+
+    let body = extract_body(&buf);
+    let parsed = AirQualityResponse::try_from(body).map_err(|e| {
+        log::error!("Failed to parse air quality JSON response: {:?}", e);
+        api_error(body).unwrap_or(AppError::from(e))
+    })?;
+
+    log::debug!("{parsed:?}");
+
+    Ok(parsed)
+}
+
+/// Build an Open-Meteo Air Quality HTTP request for the given latitude, longitude and timezone.
+fn build_air_quality_query(latitude: &str, longitude: &str, timezone: &str) -> Result<String> {
     let lat_enc = url_encode_component(latitude)?;
     let long_enc = url_encode_component(longitude)?;
     let tz_enc = url_encode_component(timezone)?;
-    let temp_unit_enc = url_encode_component(temperature_unit)?;
-    let windspeed_unit_enc = url_encode_component(windspeed_unit)?;
 
     let mut query: String = String::new();
     write!(
         query,
-        "/v1/forecast?latitude={}&longitude={}&daily={}&current={}&timezone={}&temperature_unit={}&wind_speed_unit={}&timeformat=unixtime",
-        lat_enc, long_enc, DAILY_FIELDS, CURRENT_FIELDS, tz_enc, temp_unit_enc, windspeed_unit_enc
+        "/v1/air-quality?latitude={}&longitude={}&current={}&timezone={}&timeformat=unixtime",
+        lat_enc, long_enc, AIR_QUALITY_FIELDS, tz_enc
     )
     .map_err(|_| AppError::HttpRequestFailed)?;
     Ok(query)
 }
+
+/// Search the Open-Meteo geocoding API for places called `name`.
+///
+/// Returns a parsed `GeocodingResponse` on success or an error `Result` on failure.
+pub async fn fetch_geocoding(
+    stack: embassy_net::Stack<'static>,
+    name: &str,
+) -> Result<GeocodingResponse> {
+    let name_enc = url_encode_component(name)?;
+    let mut query: String = String::new();
+    write!(
+        query,
+        "/v1/search?name={}&count={}&language=en&format=json",
+        name_enc, MAX_GEOCODING_RESULTS
+    )
+    .map_err(|_| AppError::HttpRequestFailed)?;
+
+    let buf = http_get_raw(stack, OPEN_METEO_GEOCODING_URL, &query, Some(HEADERS_STR))
+        .await
+        .map_err(|e| {
+            log::error!("Fetching geocoding data failed: {:?}", e);
+            e
+        })?;
+
+    let body = extract_body(&buf);
+    let parsed = GeocodingResponse::try_from(body).map_err(|e| {
+        log::error!("Failed to parse geocoding JSON response: {:?}", e);
+        api_error(body).unwrap_or(AppError::from(e))
+    })?;
+
+    log::debug!("{parsed:?}");
+
+    Ok(parsed)
+}
@@ -0,0 +1,118 @@
+use heapless::Vec;
+use serde::Deserialize;
+
+use crate::{
+    location::Location,
+    storage::{self, Slot},
+    weather::{
+        model::{BatchSummaryResponse, LocationSummary, MAX_SUMMARY_LOCATIONS, OpenMeteoResponse},
+        validate::ValidatedForecast,
+    },
+};
+
+/// Room for a serialized `Location` with every character of the name escaped
+const LOCATION_JSON_LEN: usize = 256;
+
+/// Last forecast fetched successfully, kept in flash for when a later fetch fails
+#[derive(Deserialize)]
+struct SavedForecast {
+    location: Location,
+    forecast: OpenMeteoResponse,
+}
+
+/// Last multi-location summary fetched successfully
+#[derive(Deserialize)]
+struct SavedSummary {
+    locations: Vec<Location, MAX_SUMMARY_LOCATIONS>,
+    /// Always an array, also for a single location
+    summary: Vec<LocationSummary, MAX_SUMMARY_LOCATIONS>,
+}
+
+/// Save the forecast response `body` fetched for `location`, to be shown again when the next
+/// fetch fails.
+///
+/// The body is stored as received and parsed again on load, so the record is no larger than
+/// the response. A failure is only logged, as the fresh forecast can still be shown.
+pub async fn save_forecast(location: &Location, body: &[u8]) {
+    let Ok(location_json) = serde_json_core::to_string::<_, LOCATION_JSON_LEN>(location) else {
+        log::warn!("Failed to serialize the location of the saved forecast");
+        return;
+    };
+    let parts: [&[u8]; 5] = [
+        b"{\"location\":",
+        location_json.as_bytes(),
+        b",\"forecast\":",
+        body,
+        b"}",
+    ];
+    if let Err(e) = storage::save_parts(Slot::Forecast, &parts).await {
+        log::warn!("Failed to save forecast: {:?}", e);
+    }
+}
+
+/// Load the last saved forecast and the location it was fetched for.
+///
+/// Returns `None` when nothing is saved or the forecast was fetched for another place than
+/// `location`.
+pub async fn load_forecast(location: &Location) -> Option<(Location, ValidatedForecast)> {
+    let saved = storage::load::<SavedForecast>(Slot::Forecast).await?;
+    if !saved.location.same_place(location) {
+        log::info!("Saved forecast is for another location");
+        return None;
+    }
+    match ValidatedForecast::try_from(saved.forecast) {
+        Ok(forecast) => Some((saved.location, forecast)),
+        Err(e) => {
+            log::warn!("Discarding saved forecast: {}", e);
+            None
+        }
+    }
+}
+
+/// Save the summary response `body` fetched for `locations`, to be shown again when the next
+/// fetch fails. A failure is only logged, as the fresh summary can still be shown.
+pub async fn save_summary(locations: &[Location], body: &[u8]) {
+    let Ok(locations_json) =
+        serde_json_core::to_string::<_, { LOCATION_JSON_LEN * MAX_SUMMARY_LOCATIONS }>(locations)
+    else {
+        log::warn!("Failed to serialize the locations of the saved summary");
+        return;
+    };
+    // Open-Meteo answers with an object instead of an array for a single location
+    let (open, close): (&[u8], &[u8]) = if body.trim_ascii_start().starts_with(b"[") {
+        (b"", b"")
+    } else {
+        (b"[", b"]")
+    };
+
+    let parts: [&[u8]; 7] = [
+        b"{\"locations\":",
+        locations_json.as_bytes(),
+        b",\"summary\":",
+        open,
+        body,
+        close,
+        b"}",
+    ];
+    if let Err(e) = storage::save_parts(Slot::Summary, &parts).await {
+        log::warn!("Failed to save location summary: {:?}", e);
+    }
+}
+
+/// Load the last saved summary and the locations it was fetched for.
+///
+/// Returns `None` when nothing is saved or the record does not hold one summary per location.
+pub async fn load_summary() -> Option<(Vec<Location, MAX_SUMMARY_LOCATIONS>, BatchSummaryResponse)>
+{
+    let saved = storage::load::<SavedSummary>(Slot::Summary).await?;
+    if saved.summary.len() != saved.locations.len() {
+        log::warn!("Discarding saved summary that does not match its locations");
+        return None;
+    }
+    Some((
+        saved.locations,
+        BatchSummaryResponse {
+            locations: saved.summary,
+        },
+    ))
+}
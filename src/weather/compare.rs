@@ -0,0 +1,209 @@
+use core::fmt::Write;
+
+use heapless::String;
+
+use crate::units::TemperatureUnit;
+
+/// Smallest temperature difference worth mentioning, about 3 °F
+const TEMPERATURE_DELTA_C: f32 = 1.5;
+/// Today's peak wind must be this many times yesterday's, or the other way round
+const WIND_RATIO: f32 = 1.5;
+/// Ignore wind changes below this (9 km/h, 5.6 mph) so light breezes do not count
+const WIND_DELTA_MS: f32 = 2.5;
+const WET_PROBABILITY: i32 = 50;
+const DRY_PROBABILITY: i32 = 20;
+
+/// The readings of one day that are compared, in fixed units so that the thresholds do not
+/// depend on the display units
+#[derive(Debug, Clone, Copy)]
+pub struct DayReadings {
+    /// °C
+    pub temperature_max: f32,
+    /// m/s
+    pub wind_speed_max: f32,
+    /// mm
+    pub precipitation_sum: f32,
+    pub precipitation_probability: i32,
+}
+
+/// Most notable change from yesterday to today
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DayComparison {
+    /// Degrees warmer, in °C
+    Warmer(f32),
+    /// Degrees colder, in °C
+    Colder(f32),
+    Windier,
+    Calmer,
+    Wetter,
+    Drier,
+    Similar,
+}
+
+impl DayComparison {
+    /// Compare today against yesterday, preferring temperature, then wind, then precipitation
+    pub fn between(yesterday: &DayReadings, today: &DayReadings) -> Self {
+        let temperature = today.temperature_max - yesterday.temperature_max;
+        if temperature >= TEMPERATURE_DELTA_C {
+            return DayComparison::Warmer(temperature);
+        }
+        if temperature <= -TEMPERATURE_DELTA_C {
+            return DayComparison::Colder(-temperature);
+        }
+
+        let (wind_today, wind_yesterday) = (today.wind_speed_max, yesterday.wind_speed_max);
+        if wind_today - wind_yesterday >= WIND_DELTA_MS && wind_today >= wind_yesterday * WIND_RATIO
+        {
+            return DayComparison::Windier;
+        }
+        if wind_yesterday - wind_today >= WIND_DELTA_MS && wind_yesterday >= wind_today * WIND_RATIO
+        {
+            return DayComparison::Calmer;
+        }
+
+        if yesterday.precipitation_sum == 0.0 && today.precipitation_probability >= WET_PROBABILITY
+        {
+            return DayComparison::Wetter;
+        }
+        if yesterday.precipitation_sum > 0.0 && today.precipitation_probability < DRY_PROBABILITY {
+            return DayComparison::Drier;
+        }
+        DayComparison::Similar
+    }
+
+    /// Short description that fits beside the "TODAY" title, e.g. "7F warmer" or "much windier",
+    /// with a temperature change in `temp_unit`
+    pub fn phrase(&self, temp_unit: TemperatureUnit) -> String<16> {
+        // A difference of temperatures scales without the offset between the scales
+        let degrees = |celsius: f32| match temp_unit {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 1.8,
+        };
+        let mut out = String::new();
+        let _ = match *self {
+            DayComparison::Warmer(change) => {
+                write!(out, "{:.0}{} warmer", degrees(change), temp_unit.symbol())
+            }
+            DayComparison::Colder(change) => {
+                write!(out, "{:.0}{} colder", degrees(change), temp_unit.symbol())
+            }
+            DayComparison::Windier => out.write_str("much windier"),
+            DayComparison::Calmer => out.write_str("much calmer"),
+            DayComparison::Wetter => out.write_str("wetter"),
+            DayComparison::Drier => out.write_str("drier"),
+            DayComparison::Similar => out.write_str("like yesterday"),
+        };
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(temperature: f32, wind: f32, precipitation: f32, probability: i32) -> DayReadings {
+        DayReadings {
+            temperature_max: temperature,
+            wind_speed_max: wind,
+            precipitation_sum: precipitation,
+            precipitation_probability: probability,
+        }
+    }
+
+    const YESTERDAY: DayReadings = DayReadings {
+        temperature_max: 15.0,
+        wind_speed_max: 3.0,
+        precipitation_sum: 0.0,
+        precipitation_probability: 0,
+    };
+
+    #[test]
+    fn temperature_change_from_one_and_a_half_degrees_celsius() {
+        let between =
+            |temperature| DayComparison::between(&YESTERDAY, &day(temperature, 3.0, 0.0, 0));
+        assert_eq!(between(16.5), DayComparison::Warmer(1.5));
+        assert_eq!(between(13.5), DayComparison::Colder(1.5));
+        assert_eq!(between(16.4), DayComparison::Similar);
+        assert_eq!(between(13.6), DayComparison::Similar);
+    }
+
+    #[test]
+    fn wind_change_needs_both_difference_and_ratio() {
+        let between = |wind| DayComparison::between(&YESTERDAY, &day(15.0, wind, 0.0, 0));
+        assert_eq!(between(5.5), DayComparison::Windier);
+        // 2.5 m/s more but less than 1.5 times as much
+        assert_eq!(
+            DayComparison::between(&day(15.0, 6.0, 0.0, 0), &day(15.0, 8.5, 0.0, 0)),
+            DayComparison::Similar
+        );
+        // 1.5 times as much but less than 2.5 m/s more
+        assert_eq!(
+            DayComparison::between(&day(15.0, 2.0, 0.0, 0), &day(15.0, 3.0, 0.0, 0)),
+            DayComparison::Similar
+        );
+        assert_eq!(
+            DayComparison::between(&day(15.0, 9.0, 0.0, 0), &day(15.0, 6.0, 0.0, 0)),
+            DayComparison::Calmer
+        );
+    }
+
+    #[test]
+    fn precipitation_change_thresholds() {
+        let wet =
+            |probability| DayComparison::between(&YESTERDAY, &day(15.0, 3.0, 0.0, probability));
+        assert_eq!(wet(50), DayComparison::Wetter);
+        assert_eq!(wet(49), DayComparison::Similar);
+
+        let rainy_yesterday = day(15.0, 3.0, 0.2, 90);
+        let dry = |probability| {
+            DayComparison::between(&rainy_yesterday, &day(15.0, 3.0, 0.0, probability))
+        };
+        assert_eq!(dry(19), DayComparison::Drier);
+        assert_eq!(dry(20), DayComparison::Similar);
+    }
+
+    #[test]
+    fn temperature_wins_over_wind_and_wind_over_precipitation() {
+        let warmer_windier_wetter = day(18.0, 9.0, 0.0, 90);
+        assert_eq!(
+            DayComparison::between(&YESTERDAY, &warmer_windier_wetter),
+            DayComparison::Warmer(3.0)
+        );
+        let windier_wetter = day(15.0, 9.0, 0.0, 90);
+        assert_eq!(
+            DayComparison::between(&YESTERDAY, &windier_wetter),
+            DayComparison::Windier
+        );
+    }
+
+    #[test]
+    fn phrase_shows_the_change_in_the_display_unit() {
+        assert_eq!(
+            DayComparison::Warmer(4.0).phrase(TemperatureUnit::Fahrenheit),
+            "7F warmer"
+        );
+        assert_eq!(
+            DayComparison::Warmer(4.0).phrase(TemperatureUnit::Celsius),
+            "4C warmer"
+        );
+        assert_eq!(
+            DayComparison::Colder(10.0).phrase(TemperatureUnit::Fahrenheit),
+            "18F colder"
+        );
+    }
+
+    #[test]
+    fn phrases_fit_beside_the_title() {
+        let unit = TemperatureUnit::Fahrenheit;
+        assert_eq!(DayComparison::Similar.phrase(unit), "like yesterday");
+        for comparison in [
+            DayComparison::Colder(60.0),
+            DayComparison::Windier,
+            DayComparison::Calmer,
+            DayComparison::Wetter,
+            DayComparison::Drier,
+        ] {
+            assert!(comparison.phrase(unit).len() <= 16);
+        }
+    }
+}
@@ -1,3 +1,9 @@
+pub mod alert_source;
+pub mod alerts;
 pub mod api;
+pub mod cache;
+pub mod compare;
 pub mod model;
+pub mod pressure;
 pub mod ui;
+pub mod validate;
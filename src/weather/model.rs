@@ -1,47 +1,292 @@
-use heapless::LinearMap;
 use heapless::String;
 use heapless::Vec;
-use once_cell::sync::Lazy;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, de::Visitor};
+
+use crate::{
+    error::AppError,
+    location::Location,
+    moon::moon_info,
+    time::unix_hh_mm,
+    units::{LengthUnit, PressureUnit, SpeedUnit, TemperatureUnit},
+    weather::{
+        alerts::{WeatherAlert, WeatherAlerts},
+        validate::ValidatedForecast,
+    },
+};
 use serde_json_core::{self as json_core};
 
-// lazy static map for weather codes to descriptions
-static WEATHER_CODES: Lazy<LinearMap<i32, &'static str, 25>> = Lazy::new(|| {
-    let mut m = LinearMap::new();
-    let _ = m.insert(0, "Clear sky");
-    let _ = m.insert(1, "Mainly clear");
-    let _ = m.insert(2, "Partly cloudy");
-    let _ = m.insert(3, "Overcast");
-    let _ = m.insert(45, "Fog");
-    let _ = m.insert(48, "Rime Fog");
-    let _ = m.insert(51, "Light drizzle");
-    let _ = m.insert(53, "Moderate drizzle");
-    let _ = m.insert(55, "Dense drizzle");
-    let _ = m.insert(56, "Light freezing drizzle");
-    let _ = m.insert(57, "Freezing drizzle");
-    let _ = m.insert(61, "Light rain");
-    let _ = m.insert(63, "Moderate rain");
-    let _ = m.insert(65, "Heavy rain");
-    let _ = m.insert(66, "Light freezing rain");
-    let _ = m.insert(67, "Freezing rain");
-    let _ = m.insert(71, "Light snow fall");
-    let _ = m.insert(73, "Moderate snow fall");
-    let _ = m.insert(75, "Heavy snow fall");
-    let _ = m.insert(80, "Light rain showers");
-    let _ = m.insert(81, "Moderate rain showers");
-    let _ = m.insert(82, "Heavy rain showers");
-    let _ = m.insert(95, "Thunderstorm");
-    let _ = m.insert(96, "Thunderstorm with light hail");
-    let _ = m.insert(99, "Thunderstorm with hail");
-    m
-});
+/// WMO 4677 weather code, as reported by Open-Meteo
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(from = "i32")]
+pub enum WeatherCode {
+    ClearSky,
+    MainlyClear,
+    PartlyCloudy,
+    Overcast,
+    Fog,
+    RimeFog,
+    LightDrizzle,
+    ModerateDrizzle,
+    DenseDrizzle,
+    LightFreezingDrizzle,
+    DenseFreezingDrizzle,
+    LightRain,
+    ModerateRain,
+    HeavyRain,
+    LightFreezingRain,
+    HeavyFreezingRain,
+    LightSnow,
+    ModerateSnow,
+    HeavySnow,
+    SnowGrains,
+    LightRainShowers,
+    ModerateRainShowers,
+    ViolentRainShowers,
+    LightSnowShowers,
+    HeavySnowShowers,
+    Thunderstorm,
+    ThunderstormLightHail,
+    ThunderstormHeavyHail,
+    /// A code Open-Meteo is not documented to send
+    Unknown(i32),
+}
+
+/// How disruptive the weather of a code is, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WeatherSeverity {
+    Unknown,
+    Fair,
+    Light,
+    Moderate,
+    Heavy,
+    Severe,
+}
+
+/// Weather icons in the sprite sheets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeatherIcon {
+    Sunny,
+    MainlyClear,
+    PartlyCloudy,
+    Overcast,
+    Rain,
+    Drizzle,
+    Storms,
+    Snow,
+    Fog,
+    ClearNight,
+    MainlyClearNight,
+    PartlyCloudyNight,
+}
+
+impl From<i32> for WeatherCode {
+    fn from(code: i32) -> Self {
+        match code {
+            0 => WeatherCode::ClearSky,
+            1 => WeatherCode::MainlyClear,
+            2 => WeatherCode::PartlyCloudy,
+            3 => WeatherCode::Overcast,
+            45 => WeatherCode::Fog,
+            48 => WeatherCode::RimeFog,
+            51 => WeatherCode::LightDrizzle,
+            53 => WeatherCode::ModerateDrizzle,
+            55 => WeatherCode::DenseDrizzle,
+            56 => WeatherCode::LightFreezingDrizzle,
+            57 => WeatherCode::DenseFreezingDrizzle,
+            61 => WeatherCode::LightRain,
+            63 => WeatherCode::ModerateRain,
+            65 => WeatherCode::HeavyRain,
+            66 => WeatherCode::LightFreezingRain,
+            67 => WeatherCode::HeavyFreezingRain,
+            71 => WeatherCode::LightSnow,
+            73 => WeatherCode::ModerateSnow,
+            75 => WeatherCode::HeavySnow,
+            77 => WeatherCode::SnowGrains,
+            80 => WeatherCode::LightRainShowers,
+            81 => WeatherCode::ModerateRainShowers,
+            82 => WeatherCode::ViolentRainShowers,
+            85 => WeatherCode::LightSnowShowers,
+            86 => WeatherCode::HeavySnowShowers,
+            95 => WeatherCode::Thunderstorm,
+            96 => WeatherCode::ThunderstormLightHail,
+            99 => WeatherCode::ThunderstormHeavyHail,
+            other => WeatherCode::Unknown(other),
+        }
+    }
+}
+
+impl WeatherCode {
+    pub fn description(self) -> &'static str {
+        match self {
+            WeatherCode::ClearSky => "Clear sky",
+            WeatherCode::MainlyClear => "Mainly clear",
+            WeatherCode::PartlyCloudy => "Partly cloudy",
+            WeatherCode::Overcast => "Overcast",
+            WeatherCode::Fog => "Fog",
+            WeatherCode::RimeFog => "Rime Fog",
+            WeatherCode::LightDrizzle => "Light drizzle",
+            WeatherCode::ModerateDrizzle => "Moderate drizzle",
+            WeatherCode::DenseDrizzle => "Dense drizzle",
+            WeatherCode::LightFreezingDrizzle => "Light freezing drizzle",
+            WeatherCode::DenseFreezingDrizzle => "Freezing drizzle",
+            WeatherCode::LightRain => "Light rain",
+            WeatherCode::ModerateRain => "Moderate rain",
+            WeatherCode::HeavyRain => "Heavy rain",
+            WeatherCode::LightFreezingRain => "Light freezing rain",
+            WeatherCode::HeavyFreezingRain => "Freezing rain",
+            WeatherCode::LightSnow => "Light snow fall",
+            WeatherCode::ModerateSnow => "Moderate snow fall",
+            WeatherCode::HeavySnow => "Heavy snow fall",
+            WeatherCode::SnowGrains => "Snow grains",
+            WeatherCode::LightRainShowers => "Light rain showers",
+            WeatherCode::ModerateRainShowers => "Moderate rain showers",
+            WeatherCode::ViolentRainShowers => "Heavy rain showers",
+            WeatherCode::LightSnowShowers => "Light snow showers",
+            WeatherCode::HeavySnowShowers => "Heavy snow showers",
+            WeatherCode::Thunderstorm => "Thunderstorm",
+            WeatherCode::ThunderstormLightHail => "Thunderstorm with light hail",
+            WeatherCode::ThunderstormHeavyHail => "Thunderstorm with hail",
+            WeatherCode::Unknown(_) => "Unknown",
+        }
+    }
+
+    pub fn severity(self) -> WeatherSeverity {
+        match self {
+            WeatherCode::ClearSky
+            | WeatherCode::MainlyClear
+            | WeatherCode::PartlyCloudy
+            | WeatherCode::Overcast => WeatherSeverity::Fair,
+            WeatherCode::LightDrizzle
+            | WeatherCode::LightRain
+            | WeatherCode::LightSnow
+            | WeatherCode::SnowGrains
+            | WeatherCode::LightRainShowers
+            | WeatherCode::LightSnowShowers => WeatherSeverity::Light,
+            WeatherCode::Fog
+            | WeatherCode::RimeFog
+            | WeatherCode::ModerateDrizzle
+            | WeatherCode::DenseDrizzle
+            | WeatherCode::LightFreezingDrizzle
+            | WeatherCode::ModerateRain
+            | WeatherCode::LightFreezingRain
+            | WeatherCode::ModerateSnow
+            | WeatherCode::ModerateRainShowers => WeatherSeverity::Moderate,
+            WeatherCode::DenseFreezingDrizzle
+            | WeatherCode::HeavyRain
+            | WeatherCode::HeavyFreezingRain
+            | WeatherCode::HeavySnow
+            | WeatherCode::ViolentRainShowers
+            | WeatherCode::HeavySnowShowers => WeatherSeverity::Heavy,
+            WeatherCode::Thunderstorm
+            | WeatherCode::ThunderstormLightHail
+            | WeatherCode::ThunderstormHeavyHail => WeatherSeverity::Severe,
+            WeatherCode::Unknown(_) => WeatherSeverity::Unknown,
+        }
+    }
+
+    /// Icon for the code, with the moon variants for clear and partly cloudy skies when
+    /// `is_day` is false.
+    /// `None` for unknown codes.
+    pub fn icon(self, is_day: bool) -> Option<WeatherIcon> {
+        let icon = match self {
+            WeatherCode::ClearSky if !is_day => WeatherIcon::ClearNight,
+            WeatherCode::MainlyClear if !is_day => WeatherIcon::MainlyClearNight,
+            WeatherCode::PartlyCloudy if !is_day => WeatherIcon::PartlyCloudyNight,
+            WeatherCode::ClearSky => WeatherIcon::Sunny,
+            WeatherCode::MainlyClear => WeatherIcon::MainlyClear,
+            WeatherCode::PartlyCloudy => WeatherIcon::PartlyCloudy,
+            WeatherCode::Overcast => WeatherIcon::Overcast,
+            WeatherCode::LightRain | WeatherCode::ModerateRain | WeatherCode::HeavyRain => {
+                WeatherIcon::Rain
+            }
+            WeatherCode::LightDrizzle
+            | WeatherCode::ModerateDrizzle
+            | WeatherCode::DenseDrizzle
+            | WeatherCode::LightRainShowers
+            | WeatherCode::ModerateRainShowers
+            | WeatherCode::ViolentRainShowers => WeatherIcon::Drizzle,
+            WeatherCode::Thunderstorm
+            | WeatherCode::ThunderstormLightHail
+            | WeatherCode::ThunderstormHeavyHail => WeatherIcon::Storms,
+            WeatherCode::LightFreezingDrizzle
+            | WeatherCode::DenseFreezingDrizzle
+            | WeatherCode::LightFreezingRain
+            | WeatherCode::HeavyFreezingRain
+            | WeatherCode::LightSnow
+            | WeatherCode::ModerateSnow
+            | WeatherCode::HeavySnow
+            | WeatherCode::SnowGrains
+            | WeatherCode::LightSnowShowers
+            | WeatherCode::HeavySnowShowers => WeatherIcon::Snow,
+            WeatherCode::Fog | WeatherCode::RimeFog => WeatherIcon::Fog,
+            WeatherCode::Unknown(_) => return None,
+        };
+        Some(icon)
+    }
+}
+
+/// Displays a value with the surrounding format options, or "--" when it is missing
+pub struct OrDashes<T>(pub Option<T>);
+
+impl<T: core::fmt::Display> core::fmt::Display for OrDashes<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.0 {
+            Some(value) => value.fmt(f),
+            None => f.write_str("--"),
+        }
+    }
+}
+
+/// Deserialize a string keeping as many leading characters as fit in `N` bytes
+pub(crate) fn deserialize_truncated_str<'de, D, const N: usize>(
+    deserializer: D,
+) -> core::result::Result<String<N>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct TruncatedStrVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for TruncatedStrVisitor<N> {
+        type Value = String<N>;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a string")
+        }
+
+        fn visit_str<E>(self, value: &str) -> core::result::Result<Self::Value, E> {
+            let mut out = String::new();
+            for c in value.chars() {
+                if out.push(c).is_err() {
+                    break;
+                }
+            }
+            Ok(out)
+        }
+    }
+
+    deserializer.deserialize_str(TruncatedStrVisitor)
+}
 
 // Heapless sizing limits
-const MAX_DAYS: usize = 7;
+/// Most forecast days Open-Meteo serves, today included
+pub const MAX_FORECAST_DAYS: usize = 16;
+/// Yesterday plus up to `MAX_FORECAST_DAYS` forecast days
+const MAX_DAYS: usize = TODAY + MAX_FORECAST_DAYS;
+/// Index of today in `Daily`, after yesterday
+pub const TODAY: usize = 1;
+pub const MAX_HOURS: usize = 48;
+/// Hours of history before the current hour in `Hourly`, for the pressure trend
+pub const PAST_HOURS: usize = 3;
+const HOURLY_LEN: usize = PAST_HOURS + MAX_HOURS;
+pub const MAX_GEOCODING_RESULTS: usize = 10;
+pub const MAX_SUMMARY_LOCATIONS: usize = 5;
+const SUMMARY_DAYS: usize = 1;
 
 // Heuristic string capacities
 const BUF_LEN: usize = 32;
 const TZ_ABBR_LEN: usize = 8;
+/// Fits the error screen together with a short prefix
+pub const API_REASON_LEN: usize = 100;
 
 /// Meteo API response struct
 #[derive(Deserialize, Debug)]
@@ -55,16 +300,217 @@ pub struct OpenMeteoResponse {
     pub elevation: f32,
     pub current_units: CurrentUnits,
     pub current: Current,
+    pub hourly_units: HourlyUnits,
+    pub hourly: Hourly,
     pub daily_units: DailyUnits,
     pub daily: Daily,
 }
 
+/// Forecast together with the supplementary data fetched in the same wake cycle
+#[derive(Debug)]
+pub struct WeatherReport {
+    pub location: Location,
+    pub forecast: ValidatedForecast,
+    /// `None` when the air quality request failed
+    pub air_quality: Option<AirQualityResponse>,
+    /// `None` when the alerts request failed
+    pub alerts: Option<WeatherAlerts>,
+    /// Why the forecast could not be refreshed when this is the last saved one
+    pub fetch_error: Option<AppError>,
+}
+
+/// Saved locations and their batch forecast for the multi-location summary screen
+#[derive(Debug)]
+pub struct SummaryReport {
+    pub locations: Vec<Location, MAX_SUMMARY_LOCATIONS>,
+    /// One entry per location, in the same order
+    pub summary: BatchSummaryResponse,
+    /// Why the summary could not be refreshed when this is the last saved one
+    pub fetch_error: Option<AppError>,
+}
+
+/// Data delivered to the display task, one variant per screen
+// Sent once per wake through a single-slot channel, so the size difference costs nothing
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum WeatherScreen {
+    Station(WeatherReport),
+    Summary(SummaryReport),
+}
+
+impl WeatherScreen {
+    /// Current unix time and UTC offset of the forecast, used to schedule the next wake
+    pub fn current_time(&self) -> (i64, i32) {
+        match self {
+            WeatherScreen::Station(report) => (
+                report.forecast.current.time,
+                report.forecast.utc_offset_seconds,
+            ),
+            WeatherScreen::Summary(report) => report
+                .summary
+                .locations
+                .first()
+                .map(|l| (l.current.time, l.utc_offset_seconds))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Whether the screen shows the last saved forecast because fetching a new one failed
+    pub fn is_stale(&self) -> bool {
+        match self {
+            WeatherScreen::Station(report) => report.fetch_error.is_some(),
+            WeatherScreen::Summary(report) => report.fetch_error.is_some(),
+        }
+    }
+
+    /// Most severe weather alert in effect at the forecast's current time
+    pub fn active_alert(&self) -> Option<&WeatherAlert> {
+        match self {
+            WeatherScreen::Station(report) => report
+                .alerts
+                .as_ref()
+                .and_then(|alerts| alerts.most_severe(report.forecast.current.time)),
+            WeatherScreen::Summary(_) => None,
+        }
+    }
+}
+
+/// Batch Open-Meteo response for the multi-location summary
+#[derive(Debug)]
+pub struct BatchSummaryResponse {
+    pub locations: Vec<LocationSummary, MAX_SUMMARY_LOCATIONS>,
+}
+
+/// Compact forecast for one location of a batch request
+#[derive(Deserialize, Debug)]
+pub struct LocationSummary {
+    pub latitude: f32,
+    pub longitude: f32,
+    pub utc_offset_seconds: i32,
+    pub current_units: CurrentUnits,
+    pub current: Current,
+    pub daily_units: SummaryDailyUnits,
+    pub daily: SummaryDaily,
+}
+
+/// Today's outlook in a location summary, `None` where Open-Meteo has no data
+#[derive(Deserialize, Debug)]
+pub struct SummaryDaily {
+    pub time: Vec<i64, SUMMARY_DAYS>,
+    pub weather_code: Vec<Option<WeatherCode>, SUMMARY_DAYS>,
+    pub temperature_2m_max: Vec<Option<f32>, SUMMARY_DAYS>,
+    pub temperature_2m_min: Vec<Option<f32>, SUMMARY_DAYS>,
+    pub precipitation_probability_max: Vec<Option<i32>, SUMMARY_DAYS>,
+}
+
+/// Daily units of a location summary
+#[derive(Deserialize, Debug)]
+pub struct SummaryDailyUnits {
+    pub time: String<BUF_LEN>,
+    pub weather_code: String<BUF_LEN>,
+    pub temperature_2m_max: String<BUF_LEN>,
+    pub temperature_2m_min: String<BUF_LEN>,
+    pub precipitation_probability_max: String<BUF_LEN>,
+}
+
+impl SummaryDailyUnits {
+    /// Unit of the daily minimum and maximum temperature, `None` if not recognized
+    pub fn temperature(&self) -> Option<TemperatureUnit> {
+        TemperatureUnit::parse(&self.temperature_2m_max)
+    }
+}
+
+/// Open-Meteo Air Quality API response struct
+#[derive(Deserialize, Debug)]
+pub struct AirQualityResponse {
+    pub latitude: f32,
+    pub longitude: f32,
+    pub generationtime_ms: f32,
+    pub utc_offset_seconds: i32,
+    pub timezone: String<BUF_LEN>,
+    pub timezone_abbreviation: String<TZ_ABBR_LEN>,
+    pub current_units: AirQualityCurrentUnits,
+    pub current: AirQualityCurrent,
+}
+
+/// Current air quality data struct
+///
+/// Every value is optional: pollen is only modelled for Europe and the indices may be
+/// missing for a location.
+#[derive(Deserialize, Debug)]
+pub struct AirQualityCurrent {
+    pub time: i64,
+    pub interval: i32,
+    pub us_aqi: Option<i32>,
+    pub european_aqi: Option<i32>,
+    pub pm2_5: Option<f32>,
+    pub alder_pollen: Option<f32>,
+    pub birch_pollen: Option<f32>,
+    pub grass_pollen: Option<f32>,
+    pub mugwort_pollen: Option<f32>,
+    pub olive_pollen: Option<f32>,
+    pub ragweed_pollen: Option<f32>,
+}
+
+/// Current air quality units struct
+#[derive(Deserialize, Debug)]
+pub struct AirQualityCurrentUnits {
+    pub time: String<BUF_LEN>,
+    pub interval: String<BUF_LEN>,
+    pub us_aqi: String<BUF_LEN>,
+    pub european_aqi: String<BUF_LEN>,
+    pub pm2_5: String<BUF_LEN>,
+    pub alder_pollen: String<BUF_LEN>,
+    pub birch_pollen: String<BUF_LEN>,
+    pub grass_pollen: String<BUF_LEN>,
+    pub mugwort_pollen: String<BUF_LEN>,
+    pub olive_pollen: String<BUF_LEN>,
+    pub ragweed_pollen: String<BUF_LEN>,
+}
+
+/// Open-Meteo geocoding API response struct
+#[derive(Deserialize, Debug)]
+pub struct GeocodingResponse {
+    /// Missing when no place matched the search
+    pub results: Option<Vec<GeocodingResult, MAX_GEOCODING_RESULTS>>,
+}
+
+/// Body Open-Meteo sends with HTTP 400 when it rejects a request
+#[derive(Deserialize, Debug)]
+pub struct ApiErrorResponse {
+    pub error: bool,
+    /// Truncated to `API_REASON_LEN` bytes
+    #[serde(deserialize_with = "deserialize_truncated_str")]
+    pub reason: String<API_REASON_LEN>,
+}
+
+/// Single place returned by the geocoding API
+#[derive(Deserialize, Debug)]
+pub struct GeocodingResult {
+    pub name: String<BUF_LEN>,
+    pub latitude: f32,
+    pub longitude: f32,
+    pub country_code: Option<String<TZ_ABBR_LEN>>,
+    pub country: Option<String<BUF_LEN>>,
+    pub admin1: Option<String<BUF_LEN>>,
+    pub timezone: Option<String<BUF_LEN>>,
+}
+
 /// Current weather data struct
 #[derive(Deserialize, Debug)]
 pub struct Current {
     pub time: i64,
     pub interval: i32,
     pub temperature_2m: f32,
+    /// `None` when not requested or not supplied, see `ValidatedForecast::comfort`
+    pub apparent_temperature: Option<f32>,
+    pub relative_humidity_2m: i32,
+    pub weather_code: WeatherCode,
+    /// `1` between sunrise and sunset, `0` at night
+    pub is_day: i32,
+    pub wind_speed_10m: f32,
+    pub wind_direction_10m: i32,
+    pub surface_pressure: f32,
 }
 
 /// Current units struct
@@ -73,19 +519,121 @@ pub struct CurrentUnits {
     pub time: String<BUF_LEN>,
     pub interval: String<BUF_LEN>,
     pub temperature_2m: String<BUF_LEN>,
+    pub apparent_temperature: Option<String<BUF_LEN>>,
+    pub relative_humidity_2m: String<BUF_LEN>,
+    pub weather_code: String<BUF_LEN>,
+    pub is_day: String<BUF_LEN>,
+    pub wind_speed_10m: String<BUF_LEN>,
+    pub wind_direction_10m: String<BUF_LEN>,
+    pub surface_pressure: String<BUF_LEN>,
+}
+
+impl CurrentUnits {
+    /// Unit of the current and apparent temperature, `None` if not recognized
+    pub fn temperature(&self) -> Option<TemperatureUnit> {
+        TemperatureUnit::parse(&self.temperature_2m)
+    }
+
+    /// Unit of the current wind speed, `None` if not recognized
+    pub fn wind_speed(&self) -> Option<SpeedUnit> {
+        SpeedUnit::parse(&self.wind_speed_10m)
+    }
+
+    /// Unit of the surface pressure, `None` if not recognized
+    pub fn pressure(&self) -> Option<PressureUnit> {
+        PressureUnit::parse(&self.surface_pressure)
+    }
+}
+
+/// Hourly weather data struct. Values are `None` where Open-Meteo sends `null` because the
+/// model has no data.
+#[derive(Deserialize, Debug)]
+pub struct Hourly {
+    pub time: Vec<i64, HOURLY_LEN>,
+    pub temperature_2m: Vec<Option<f32>, HOURLY_LEN>,
+    pub precipitation_probability: Vec<Option<i32>, HOURLY_LEN>,
+    pub weather_code: Vec<Option<WeatherCode>, HOURLY_LEN>,
+    pub wind_speed_10m: Vec<Option<f32>, HOURLY_LEN>,
+    pub wind_direction_10m: Vec<Option<i32>, HOURLY_LEN>,
+    /// Sea level pressure in hPa
+    pub pressure_msl: Vec<Option<f32>, HOURLY_LEN>,
+}
+
+/// Hourly units response struct
+#[derive(Deserialize, Debug)]
+pub struct HourlyUnits {
+    pub time: String<BUF_LEN>,
+    pub temperature_2m: String<BUF_LEN>,
+    pub precipitation_probability: String<BUF_LEN>,
+    pub weather_code: String<BUF_LEN>,
+    pub wind_speed_10m: String<BUF_LEN>,
+    pub wind_direction_10m: String<BUF_LEN>,
+    pub pressure_msl: String<BUF_LEN>,
 }
 
-/// Daily weather data struct
+/// Daily weather data struct. Values are `None` where Open-Meteo sends `null`, e.g. for
+/// far-future days or variables not available at the location.
 #[derive(Deserialize, Debug)]
 pub struct Daily {
     pub time: Vec<i64, MAX_DAYS>,
-    pub weather_code: Vec<i32, MAX_DAYS>,
-    pub temperature_2m_max: Vec<f32, MAX_DAYS>,
-    pub temperature_2m_min: Vec<f32, MAX_DAYS>,
-    pub sunrise: Vec<i64, MAX_DAYS>,
-    pub sunset: Vec<i64, MAX_DAYS>,
-    pub wind_speed_10m_max: Vec<f32, MAX_DAYS>,
-    pub wind_direction_10m_dominant: Vec<i32, MAX_DAYS>,
+    pub weather_code: Vec<Option<WeatherCode>, MAX_DAYS>,
+    pub temperature_2m_max: Vec<Option<f32>, MAX_DAYS>,
+    pub temperature_2m_min: Vec<Option<f32>, MAX_DAYS>,
+    pub sunrise: Vec<Option<i64>, MAX_DAYS>,
+    pub sunset: Vec<Option<i64>, MAX_DAYS>,
+    pub wind_speed_10m_max: Vec<Option<f32>, MAX_DAYS>,
+    pub wind_direction_10m_dominant: Vec<Option<i32>, MAX_DAYS>,
+    pub precipitation_sum: Vec<Option<f32>, MAX_DAYS>,
+    pub precipitation_probability_max: Vec<Option<i32>, MAX_DAYS>,
+    pub rain_sum: Vec<Option<f32>, MAX_DAYS>,
+    pub snowfall_sum: Vec<Option<f32>, MAX_DAYS>,
+    pub precipitation_hours: Vec<Option<f32>, MAX_DAYS>,
+    pub uv_index_max: Vec<Option<f32>, MAX_DAYS>,
+    pub sunshine_duration: Vec<Option<f32>, MAX_DAYS>,
+}
+
+/// Values of one day of the daily forecast, `None` where the forecast has no data
+#[derive(Debug, Clone, Copy)]
+pub struct DayForecast {
+    pub time: i64,
+    pub weather_code: Option<WeatherCode>,
+    pub temperature_2m_max: Option<f32>,
+    pub temperature_2m_min: Option<f32>,
+    /// `None` when the forecast omits it, e.g. during polar day or night
+    pub sunrise: Option<i64>,
+    pub sunset: Option<i64>,
+    pub wind_speed_10m_max: Option<f32>,
+    pub wind_direction_10m_dominant: Option<i32>,
+    pub precipitation_sum: Option<f32>,
+    pub precipitation_probability_max: Option<i32>,
+    pub rain_sum: Option<f32>,
+    pub snowfall_sum: Option<f32>,
+    pub precipitation_hours: Option<f32>,
+    pub uv_index_max: Option<f32>,
+    pub sunshine_duration: Option<f32>,
+}
+
+impl Daily {
+    /// Values of day `index`, `None` if any series other than sunrise and sunset is too short
+    pub fn day(&self, index: usize) -> Option<DayForecast> {
+        Some(DayForecast {
+            time: *self.time.get(index)?,
+            weather_code: *self.weather_code.get(index)?,
+            temperature_2m_max: *self.temperature_2m_max.get(index)?,
+            temperature_2m_min: *self.temperature_2m_min.get(index)?,
+            sunrise: self.sunrise.get(index).copied().flatten(),
+            sunset: self.sunset.get(index).copied().flatten(),
+            wind_speed_10m_max: *self.wind_speed_10m_max.get(index)?,
+            wind_direction_10m_dominant: *self.wind_direction_10m_dominant.get(index)?,
+            precipitation_sum: *self.precipitation_sum.get(index)?,
+            precipitation_probability_max: *self.precipitation_probability_max.get(index)?,
+            rain_sum: *self.rain_sum.get(index)?,
+            snowfall_sum: *self.snowfall_sum.get(index)?,
+            precipitation_hours: *self.precipitation_hours.get(index)?,
+            uv_index_max: *self.uv_index_max.get(index)?,
+            sunshine_duration: *self.sunshine_duration.get(index)?,
+        })
+    }
 }
 
 /// Daily units response struct
@@ -99,6 +647,35 @@ pub struct DailyUnits {
     pub sunset: String<BUF_LEN>,
     pub wind_speed_10m_max: String<BUF_LEN>,
     pub wind_direction_10m_dominant: String<BUF_LEN>,
+    pub precipitation_sum: String<BUF_LEN>,
+    pub precipitation_probability_max: String<BUF_LEN>,
+    pub rain_sum: String<BUF_LEN>,
+    pub snowfall_sum: String<BUF_LEN>,
+    pub precipitation_hours: String<BUF_LEN>,
+    pub uv_index_max: String<BUF_LEN>,
+    pub sunshine_duration: String<BUF_LEN>,
+}
+
+impl DailyUnits {
+    /// Unit of the daily minimum and maximum temperature, `None` if not recognized
+    pub fn temperature(&self) -> Option<TemperatureUnit> {
+        TemperatureUnit::parse(&self.temperature_2m_max)
+    }
+
+    /// Unit of the daily maximum wind speed, `None` if not recognized
+    pub fn wind_speed(&self) -> Option<SpeedUnit> {
+        SpeedUnit::parse(&self.wind_speed_10m_max)
+    }
+
+    /// Unit of the precipitation and rain sums, `None` if not recognized
+    pub fn precipitation(&self) -> Option<LengthUnit> {
+        LengthUnit::parse(&self.precipitation_sum)
+    }
+
+    /// Unit of the snowfall sum, centimetres with metric units, `None` if not recognized
+    pub fn snowfall(&self) -> Option<LengthUnit> {
+        LengthUnit::parse(&self.snowfall_sum)
+    }
 }
 
 /// Parse the weather JSON response into an OpenMeteoResponse struct
@@ -112,11 +689,75 @@ impl core::convert::TryFrom<&[u8]> for OpenMeteoResponse {
     }
 }
 
-/// Provide a From impl so callers can do `String::from(&api_response)`
-impl From<&OpenMeteoResponse> for String<1024> {
+/// Parse the batch JSON response into a BatchSummaryResponse struct
+///
+/// Open-Meteo answers a multi-location request with an array and a single location with a
+/// plain object, so both are accepted.
+impl core::convert::TryFrom<&[u8]> for BatchSummaryResponse {
+    type Error = json_core::de::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let is_array = value
+            .iter()
+            .find(|b| !b.is_ascii_whitespace())
+            .is_some_and(|&b| b == b'[');
+        let locations = if is_array {
+            json_core::from_slice::<Vec<LocationSummary, MAX_SUMMARY_LOCATIONS>>(value)?.0
+        } else {
+            let (parsed, _consumed) = json_core::from_slice::<LocationSummary>(value)?;
+            let mut locations = Vec::new();
+            let _ = locations.push(parsed);
+            locations
+        };
+        Ok(BatchSummaryResponse { locations })
+    }
+}
+
+/// Parse the air quality JSON response into an AirQualityResponse struct
+impl core::convert::TryFrom<&[u8]> for AirQualityResponse {
+    type Error = json_core::de::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let (parsed, _consumed) = json_core::from_slice::<AirQualityResponse>(value)?;
+        Ok(parsed)
+    }
+}
+
+/// Parse the geocoding JSON response into a GeocodingResponse struct
+impl core::convert::TryFrom<&[u8]> for GeocodingResponse {
+    type Error = json_core::de::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let (parsed, _consumed) = json_core::from_slice::<GeocodingResponse>(value)?;
+        Ok(parsed)
+    }
+}
+
+/// Parse an Open-Meteo error JSON response into an ApiErrorResponse struct
+impl core::convert::TryFrom<&[u8]> for ApiErrorResponse {
+    type Error = json_core::de::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let (parsed, _consumed) = json_core::from_slice::<ApiErrorResponse>(value)?;
+        Ok(parsed)
+    }
+}
+
+/// Longest line of a `ForecastSummary`
+const SUMMARY_LINE_LEN: usize = 96;
+/// Room for the location and moon lines and one line per forecast day
+const FORECAST_SUMMARY_LEN: usize = (3 + MAX_DAYS) * SUMMARY_LINE_LEN;
+/// Last line of a `ForecastSummary` that ran out of room
+const SUMMARY_CUT: &str = "…\n";
+
+/// Human-readable summary of a forecast, as printed by the console
+pub type ForecastSummary = String<FORECAST_SUMMARY_LEN>;
+
+/// Provide a From impl so callers can do `ForecastSummary::from(&api_response)`
+impl From<&OpenMeteoResponse> for ForecastSummary {
     /// Build a small human-readable summary using a heapless string
     fn from(parsed: &OpenMeteoResponse) -> Self {
-        let mut out: String<1024> = String::new();
+        let mut out = ForecastSummary::new();
         use core::fmt::Write as _;
         let _ = write!(
             out,
@@ -124,17 +765,45 @@ impl From<&OpenMeteoResponse> for String<1024> {
             parsed.timezone, parsed.timezone_abbreviation, parsed.latitude, parsed.longitude
         );
 
-        for (i, _) in parsed.daily.time.iter().enumerate() {
-            let _ = writeln!(
-                out,
-                "{}  {:.1}C / {:.1}C {}",
-                parsed.daily.time[i],
-                parsed.daily.temperature_2m_max[i],
-                parsed.daily.temperature_2m_min[i],
-                WEATHER_CODES
-                    .get(&parsed.daily.weather_code[i])
-                    .unwrap_or(&"Unknown"),
+        // The display has no room for moonrise and moonset, so they are only listed here
+        let moon = moon_info(
+            parsed.current.time,
+            parsed.utc_offset_seconds,
+            parsed.latitude,
+            parsed.longitude,
+        );
+        let hh_mm =
+            |ts: Option<i64>| OrDashes(ts.and_then(|ts| unix_hh_mm(ts, parsed.utc_offset_seconds)));
+        let _ = writeln!(
+            out,
+            "moon: {} {:.0}%, rise {}, set {}\n",
+            moon.phase.name(),
+            moon.illumination * 100.0,
+            hh_mm(moon.moonrise),
+            hh_mm(moon.moonset),
+        );
+
+        for day in (0..parsed.daily.time.len()).map_while(|i| parsed.daily.day(i)) {
+            // Each day is written whole or, with unexpectedly long units, not at all
+            let mut line: String<SUMMARY_LINE_LEN> = String::new();
+            let written = writeln!(
+                line,
+                "{}  {:.1}{} / {:.1}{} {} ({}% {:.2}{})",
+                day.time,
+                OrDashes(day.temperature_2m_max),
+                parsed.daily_units.temperature_2m_max,
+                OrDashes(day.temperature_2m_min),
+                parsed.daily_units.temperature_2m_min,
+                day.weather_code.map_or("--", WeatherCode::description),
+                OrDashes(day.precipitation_probability_max),
+                OrDashes(day.precipitation_sum),
+                parsed.daily_units.precipitation_sum,
             );
+            if written.is_err() || out.len() + line.len() + SUMMARY_CUT.len() > out.capacity() {
+                let _ = out.push_str(SUMMARY_CUT);
+                break;
+            }
+            let _ = out.push_str(&line);
         }
 
         out
@@ -0,0 +1,72 @@
+use crate::{
+    units::{Pressure, PressureUnit},
+    weather::model::Hourly,
+};
+
+/// Hours over which the barometric tendency is measured
+const TENDENCY_HOURS: usize = 3;
+/// Smallest 3-hour change, in hPa, reported as rising or falling. Smaller changes are
+/// "steady" or "slowly" in the Met Office tendency terms.
+const TENDENCY_THRESHOLD_HPA: f32 = 1.6;
+
+/// Direction of the sea level pressure change over three hours
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureTendency {
+    Rising,
+    Steady,
+    Falling,
+}
+
+impl PressureTendency {
+    fn from_change(change_hpa: f32) -> Self {
+        if change_hpa >= TENDENCY_THRESHOLD_HPA {
+            PressureTendency::Rising
+        } else if change_hpa <= -TENDENCY_THRESHOLD_HPA {
+            PressureTendency::Falling
+        } else {
+            PressureTendency::Steady
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PressureTendency::Rising => "rising",
+            PressureTendency::Steady => "steady",
+            PressureTendency::Falling => "falling",
+        }
+    }
+}
+
+/// Current sea level pressure and its 3-hour tendency
+#[derive(Debug, Clone, Copy)]
+pub struct PressureTrend {
+    pub pressure_hpa: f32,
+    /// Change over the 3 hours up to now, or the next 3 hours without enough history
+    pub change_hpa: f32,
+    pub tendency: PressureTendency,
+}
+
+impl PressureTrend {
+    /// Trend at unix time `now` from the hourly `pressure_msl` series, `None` without at least
+    /// 3 hours of data around `now` or when either reading is missing
+    pub fn from_hourly(hourly: &Hourly, now: i64) -> Option<Self> {
+        let pressure = &hourly.pressure_msl;
+        let current = hourly.time.iter().rposition(|&ts| ts <= now)?;
+        let pressure_hpa = (*pressure.get(current)?)?;
+
+        let change_hpa = match current.checked_sub(TENDENCY_HOURS) {
+            Some(past) => pressure_hpa - (*pressure.get(past)?)?,
+            None => (*pressure.get(current + TENDENCY_HOURS)?)? - pressure_hpa,
+        };
+
+        Some(PressureTrend {
+            pressure_hpa,
+            change_hpa,
+            tendency: PressureTendency::from_change(change_hpa),
+        })
+    }
+
+    pub fn pressure(&self) -> Pressure {
+        Pressure::new(self.pressure_hpa, PressureUnit::Hectopascal)
+    }
+}
@@ -2,20 +2,38 @@ use embedded_graphics::{
     image::ImageRaw,
     pixelcolor::{BinaryColor, Gray2},
     prelude::*,
-    primitives::Rectangle,
+    primitives::{Circle, Line, PrimitiveStyle, Rectangle},
 };
+use libm::{cosf, sqrtf};
 
 use core::fmt::Write;
 use heapless::String;
 use once_cell::sync::Lazy;
 
 use crate::{
-    error::Result,
-    graphics::{draw_binary_color_image, draw_image, draw_text, draw_text_xy_wh},
-    time::{format_date_unix, short_dow_unix, unix_hh_mm},
-    weather::model::OpenMeteoResponse,
+    config::AIR_QUALITY_INDEX,
+    error::{AppError, Result},
+    graphics::{draw_banner, draw_binary_color_image, draw_image, draw_text, draw_text_xy_wh},
+    moon::{MoonInfo, moon_info},
+    settings::display_units,
+    sun::{civil_twilight_unix, day_length_secs, sunrise_sunset_unix},
+    time::{format_date_unix, format_short_date_unix, short_dow_unix, unix_hh_mm},
+    units::{Length, LengthUnit, PressureUnit, Speed, SpeedUnit, Temperature, TemperatureUnit},
+    weather::{
+        alerts::WeatherAlert,
+        compare::{DayComparison, DayReadings},
+        model::{
+            AirQualityResponse, LocationSummary, OrDashes, SummaryReport, TODAY, WeatherCode,
+            WeatherIcon, WeatherReport,
+        },
+        pressure::{PressureTendency, PressureTrend},
+        validate::ValidatedForecast,
+    },
 };
 
+/// Rows in the forecast panel
+const FUTURE_ROWS: usize = 6;
+
 // load img data at compile time into static storage
 static WEATHER_BG: Lazy<ImageRaw<'static, BinaryColor>> = Lazy::new(|| {
     ImageRaw::<BinaryColor>::new(
@@ -26,68 +44,194 @@ static WEATHER_BG: Lazy<ImageRaw<'static, BinaryColor>> = Lazy::new(|| {
 
 static WEATHER_ICONS_20PX: Lazy<ImageRaw<'static, Gray2>> = Lazy::new(|| {
     ImageRaw::<Gray2>::new(
-        include_bytes!("../../resources/weather_icons_20px_60x60_2b.raw"),
+        include_bytes!("../../resources/weather_icons_20px_60x80_2b.raw"),
         60,
     )
 });
 
 static WEATHER_ICONS_70PX: Lazy<ImageRaw<'static, Gray2>> = Lazy::new(|| {
     ImageRaw::<Gray2>::new(
-        include_bytes!("../../resources/weather_icons_70px_210x210_2b.raw"),
+        include_bytes!("../../resources/weather_icons_70px_210x280_2b.raw"),
         210,
     )
 });
 
-/// Draw the full weather station UI into `buffer` using `weather_data`.
+/// Draw the full weather station UI into `buffer` using `report`.
 ///
 /// Returns `Ok(())` on success or an error `Result` on failure.
-pub fn draw_weather_station_view<D>(weather_data: &OpenMeteoResponse, buffer: &mut D) -> Result<()>
+pub fn draw_weather_station_view<D>(report: &WeatherReport, buffer: &mut D) -> Result<()>
 where
     D: DrawTarget<Color = Gray2> + OriginDimensions,
     <D as DrawTarget>::Error: core::fmt::Debug,
 {
+    let weather_data = &report.forecast;
+    let today = weather_data.today();
     draw_background_image(buffer)?;
-    draw_today_weather_icon(*weather_data.daily.weather_code.first().unwrap(), buffer)?;
-    let today_date = format_date_unix(
-        *weather_data.daily.time.first().unwrap(),
-        weather_data.utc_offset_seconds,
-    )
-    .unwrap();
-    draw_today_date(&today_date, buffer)?;
-    draw_today_lat_long(weather_data.latitude, weather_data.longitude, buffer)?;
+    draw_today_weather_icon(
+        weather_data.current.weather_code,
+        weather_data.current.is_day != 0,
+        buffer,
+    )?;
+    if let Some(today_date) = format_short_date_unix(today.time, weather_data.utc_offset_seconds) {
+        draw_today_date(&today_date, buffer)?;
+    }
+    if let Some(trend) = PressureTrend::from_hourly(&weather_data.hourly, weather_data.current.time)
+    {
+        draw_today_pressure_trend(&trend, buffer)?;
+    }
+    draw_today_comparison(weather_data, buffer)?;
+    draw_today_lat_long(
+        &report.location.name,
+        weather_data.latitude,
+        weather_data.longitude,
+        buffer,
+    )?;
+    if let Some(air_quality) = &report.air_quality {
+        draw_today_air_quality(air_quality, buffer)?;
+    }
+    draw_today_now(weather_data, buffer)?;
+
+    // Missing values and values in units that are not recognized are shown as "--"
+    let units = display_units();
+    let daily_units = &weather_data.daily_units;
+    let temp_unit = daily_units.temperature();
+    let temperature =
+        |value: Option<f32>| Some(Temperature::new(value?, temp_unit?).to(units.temperature));
     draw_today_high_low(
-        *weather_data.daily.temperature_2m_max.first().unwrap(),
-        *weather_data.daily.temperature_2m_min.first().unwrap(),
-        &weather_data
-            .daily_units
-            .temperature_2m_max
-            .chars()
-            .last()
-            .unwrap(),
+        temperature(today.temperature_2m_max),
+        temperature(today.temperature_2m_min),
         buffer,
     )?;
+    let wind_speed = |value: Option<f32>, unit: Option<SpeedUnit>| {
+        Some(Speed::new(value?, unit?).to(units.wind_speed))
+    };
     draw_today_wind(
-        *weather_data.daily.wind_speed_10m_max.first().unwrap(),
-        *weather_data
-            .daily
-            .wind_direction_10m_dominant
-            .first()
-            .unwrap(),
-        weather_data.daily_units.wind_speed_10m_max.as_str(),
+        wind_speed(
+            Some(weather_data.current.wind_speed_10m),
+            weather_data.current_units.wind_speed(),
+        ),
+        weather_data.current.wind_direction_10m,
+        wind_speed(today.wind_speed_10m_max, daily_units.wind_speed()),
         buffer,
     )?;
-    let sunrise_time = unix_hh_mm(
-        *weather_data.daily.sunrise.first().unwrap(),
-        weather_data.utc_offset_seconds,
-    )
-    .unwrap();
-    let sunset_time = unix_hh_mm(
-        *weather_data.daily.sunset.first().unwrap(),
+    let precip_unit = daily_units.precipitation();
+    let snowfall_unit = daily_units.snowfall();
+    let amount = |value: Option<f32>, unit: Option<LengthUnit>| {
+        Some(Length::new(value?, unit?).to(units.precipitation))
+    };
+    draw_today_precipitation(
+        today.precipitation_probability_max,
+        amount(today.precipitation_sum, precip_unit),
+        amount(today.rain_sum, precip_unit),
+        amount(today.snowfall_sum, snowfall_unit),
+        today.precipitation_hours,
+        buffer,
+    )?;
+    let (sunrise, sunset) = today_sunrise_sunset(weather_data);
+    let format_time = |ts: Option<i64>| {
+        ts.and_then(|ts| unix_hh_mm(ts, weather_data.utc_offset_seconds))
+            .unwrap_or_else(|| String::try_from("--:--").unwrap())
+    };
+    draw_today_sunrise_sunset(&format_time(sunrise), &format_time(sunset), buffer)?;
+    draw_today_uv_sunshine(today.uv_index_max, today.sunshine_duration, buffer)?;
+    let moon = moon_info(
+        weather_data.current.time,
         weather_data.utc_offset_seconds,
-    )
-    .unwrap();
-    draw_today_sunrise_sunset(&sunrise_time, &sunset_time, buffer)?;
-    draw_future_weather_view(weather_data, buffer)
+        weather_data.latitude,
+        weather_data.longitude,
+    );
+    draw_today_moon(&moon, weather_data.latitude, buffer)?;
+    draw_future_weather_view(weather_data, FUTURE_ROWS, buffer)?;
+    if let Some(alert) = report
+        .alerts
+        .as_ref()
+        .and_then(|alerts| alerts.most_severe(weather_data.current.time))
+    {
+        draw_alert_banner(alert, weather_data.utc_offset_seconds, buffer)?;
+    }
+    if let Some(error) = &report.fetch_error {
+        draw_stale_banner(
+            weather_data.current.time,
+            weather_data.utc_offset_seconds,
+            error,
+            buffer,
+        )?;
+    }
+    Ok(())
+}
+
+/// Draw when the shown forecast was fetched as a banner across the section titles, with a
+/// badge naming the kind of error that kept it from being refreshed
+fn draw_stale_banner<D>(
+    since: i64,
+    utc_offset_seconds: i32,
+    error: &AppError,
+    buffer: &mut D,
+) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    let mut banner_buf: String<24> = String::new();
+    write!(&mut banner_buf, "Stale since").unwrap();
+    if let (Some(dow), Some(hh_mm)) = (
+        short_dow_unix(since, utc_offset_seconds),
+        unix_hh_mm(since, utc_offset_seconds),
+    ) {
+        write!(&mut banner_buf, " {} {}", dow, hh_mm).unwrap();
+    }
+    draw_banner(&banner_buf, Point::zero(), Size::new(296, 14), buffer)?;
+
+    // White badge at the right end of the banner, 6x10 px per character
+    let badge = error_badge_text(error);
+    let width = 6 * badge.len() as u32 + 6;
+    let top_left = Point::new(292 - width as i32, 1);
+    Rectangle::new(top_left, Size::new(width, 12))
+        .into_styled(PrimitiveStyle::with_fill(Gray2::WHITE))
+        .draw(buffer)
+        .map_err(|e| {
+            log::error!("Failed to draw error badge to display buffer: {:?}", e);
+            AppError::GraphicsError
+        })?;
+    draw_text(
+        badge,
+        top_left + Point::new(3, 1),
+        Size::new(width, 0),
+        buffer,
+    )?;
+
+    log::info!("Stale banner drawn successfully: {}", error);
+    Ok(())
+}
+
+/// Draw the alert event and end time as a banner across the section titles
+fn draw_alert_banner<D>(alert: &WeatherAlert, utc_offset_seconds: i32, buffer: &mut D) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    // 49 characters fill the screen width
+    let mut banner_buf: String<49> = String::new();
+    write!(&mut banner_buf, "! {}", alert.event).unwrap();
+    if let Some(expires) = alert.expires
+        && let (Some(dow), Some(hh_mm)) = (
+            short_dow_unix(expires, utc_offset_seconds),
+            unix_hh_mm(expires, utc_offset_seconds),
+        )
+    {
+        // Drop the day, then the whole end time, when the event name is long
+        let mut until_buf: String<16> = String::new();
+        write!(&mut until_buf, " until {} {}", dow, hh_mm).unwrap();
+        if banner_buf.push_str(&until_buf).is_err() {
+            until_buf.clear();
+            write!(&mut until_buf, " until {}", hh_mm).unwrap();
+            let _ = banner_buf.push_str(&until_buf);
+        }
+    }
+    draw_banner(&banner_buf, Point::zero(), Size::new(296, 14), buffer)?;
+
+    log::info!("Alert banner drawn successfully");
+    Ok(())
 }
 
 /// Draw the today weather view onto the display buffer
@@ -96,73 +240,279 @@ where
     D: DrawTarget<Color = Gray2> + OriginDimensions,
     <D as DrawTarget>::Error: core::fmt::Debug,
 {
-    draw_text_xy_wh(date, 8, 16, 296, 0, buffer)?;
+    // Short enough to leave room for the pressure trend
+    draw_text_xy_wh(date, 8, 16, 87, 0, buffer)?;
 
     log::info!("Today's date drawn successfully");
     Ok(())
 }
 
-/// Draw the today weather view onto the display buffer
-fn draw_today_lat_long<D>(lat: f32, long: f32, buffer: &mut D) -> Result<()>
+/// Draw how today compares with yesterday beside the "TODAY" title
+fn draw_today_comparison<D>(weather_data: &ValidatedForecast, buffer: &mut D) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    let (Some(yesterday), Some(today)) = (
+        day_readings(weather_data, TODAY - 1),
+        day_readings(weather_data, TODAY),
+    ) else {
+        log::warn!("No data for yesterday to compare today against");
+        return Ok(());
+    };
+    let comparison = DayComparison::between(&yesterday, &today);
+
+    // 16 characters fit between the title and the right edge of the panel
+    let phrase = comparison.phrase(display_units().temperature);
+    draw_banner(&phrase, Point::new(78, 0), Size::new(98, 14), buffer)?;
+
+    log::info!("Comparison with yesterday drawn successfully: {}", phrase);
+    Ok(())
+}
+
+/// Readings of day `index` of the daily forecast in the units `DayReadings` compares, `None` if
+/// the day or one of its readings is missing or a unit is not recognized
+fn day_readings(weather_data: &ValidatedForecast, index: usize) -> Option<DayReadings> {
+    let (day, units) = (weather_data.daily.day(index)?, &weather_data.daily_units);
+    let temperature = Temperature::new(day.temperature_2m_max?, units.temperature()?);
+    let wind_speed = Speed::new(day.wind_speed_10m_max?, units.wind_speed()?);
+    let precipitation = Length::new(day.precipitation_sum?, units.precipitation()?);
+    Some(DayReadings {
+        temperature_max: temperature.to(TemperatureUnit::Celsius).value,
+        wind_speed_max: wind_speed.to(SpeedUnit::MetresPerSecond).value,
+        precipitation_sum: precipitation.to(LengthUnit::Millimetre).value,
+        precipitation_probability: day.precipitation_probability_max?,
+    })
+}
+
+/// Draw the place name, or the latitude and longitude if unnamed, onto the display buffer
+fn draw_today_lat_long<D>(name: &str, lat: f32, long: f32, buffer: &mut D) -> Result<()>
 where
     D: DrawTarget<Color = Gray2> + OriginDimensions,
     <D as DrawTarget>::Error: core::fmt::Debug,
 {
-    // Draw the Latitute and Longitude
+    if !name.is_empty() {
+        draw_text_xy_wh(name, 8, 27, 87, 0, buffer)?;
+        log::info!("Place name drawn successfully");
+        return Ok(());
+    }
+
+    // Draw the Latitute and Longitude, short enough to leave room for the air quality
     let mut lat_long_buf: String<24> = String::new();
-    write!(&mut lat_long_buf, "({:.4}, {:.4})", lat, long).unwrap();
-    draw_text_xy_wh(&lat_long_buf, 8, 27, 296, 0, buffer)?;
+    write!(&mut lat_long_buf, "{:.2},{:.2}", lat, long).unwrap();
+    draw_text_xy_wh(&lat_long_buf, 8, 27, 87, 0, buffer)?;
 
     log::info!("lat, long drawn successfully");
     Ok(())
 }
 
-fn draw_today_high_low<D>(high: f32, low: f32, temp_unit: &char, buffer: &mut D) -> Result<()>
+/// Draw the air quality category for the configured index onto the display buffer
+fn draw_today_air_quality<D>(air_quality: &AirQualityResponse, buffer: &mut D) -> Result<()>
 where
     D: DrawTarget<Color = Gray2> + OriginDimensions,
     <D as DrawTarget>::Error: core::fmt::Debug,
 {
+    let category = match AIR_QUALITY_INDEX {
+        "european" => air_quality.current.european_aqi.map(european_aqi_text),
+        _ => air_quality.current.us_aqi.map(us_aqi_text),
+    };
+    let Some(category) = category else {
+        log::warn!("No {} AQI value available", AIR_QUALITY_INDEX);
+        return Ok(());
+    };
+
+    let mut aqi_buf: String<16> = String::new();
+    write!(&mut aqi_buf, "AQI {}", category).unwrap();
+    draw_text_xy_wh(&aqi_buf, 95, 27, 85, 0, buffer)?;
+
+    log::info!("Air quality drawn successfully");
+    Ok(())
+}
+
+/// Draw the current temperature, feels-like temperature and humidity onto the display buffer
+fn draw_today_now<D>(weather_data: &ValidatedForecast, buffer: &mut D) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    let current = &weather_data.current;
+    let display_unit = display_units().temperature;
+    // Shown as "--" when the unit is not recognized
+    let temp_unit = weather_data.current_units.temperature();
+    let temperature = temp_unit.map(|unit| {
+        Temperature::new(current.temperature_2m, unit)
+            .to(display_unit)
+            .value
+    });
+    // Fall back to the feels-like temperature computed on the device
+    let comfort = weather_data.comfort();
+    let apparent = current
+        .apparent_temperature
+        .zip(temp_unit)
+        .map(|(value, unit)| Temperature::new(value, unit))
+        .or(comfort.map(|comfort| comfort.feels_like))
+        .map(|apparent| apparent.to(display_unit).value)
+        .or(temperature);
+
+    // 14 characters fit beside the icon, so the humidity is dropped when it does not fit
+    let mut now_buf: String<14> = String::new();
+    write!(
+        &mut now_buf,
+        "{:.0}{} ~{:.0}{}",
+        OrDashes(temperature),
+        display_unit.symbol(),
+        OrDashes(apparent),
+        display_unit.symbol()
+    )
+    .unwrap();
+    let mut humidity_buf: String<6> = String::new();
+    write!(&mut humidity_buf, " {}%", current.relative_humidity_2m).unwrap();
+    let _ = now_buf.push_str(&humidity_buf);
+    draw_text_xy_wh(&now_buf, 95, 36, 85, 0, buffer)?;
+
+    log::info!(
+        "Current conditions drawn successfully: {}, day {}, {:.0}{} {}",
+        current.weather_code.description(),
+        current.is_day,
+        current.surface_pressure,
+        weather_data.current_units.surface_pressure,
+        wind_dir_text(current.wind_direction_10m)
+    );
+    if let Some(comfort) = comfort {
+        log::info!(
+            "Feels {}: dew point {:.0}, heat index {:.0}, wind chill {:.0}",
+            comfort.comfort.name(),
+            comfort.dew_point.value,
+            comfort.heat_index.value,
+            comfort.wind_chill.value
+        );
+    }
+    Ok(())
+}
+
+fn draw_today_high_low<D>(
+    high: Option<Temperature>,
+    low: Option<Temperature>,
+    buffer: &mut D,
+) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    let unit = display_units().temperature;
     let mut temp_buf: String<8> = String::new();
 
     // Draw the low temperatures
     temp_buf.clear();
-    write!(&mut temp_buf, "{:.0}{}", low, temp_unit).unwrap();
+    write!(
+        &mut temp_buf,
+        "{:.0}{}",
+        OrDashes(low.map(|low| low.value)),
+        unit.symbol()
+    )
+    .unwrap();
     draw_text_xy_wh(&temp_buf, 100, 60, 80, 0, buffer)?;
     log::info!("Low temp drawn successfully");
 
     // Draw the high temperature
     temp_buf.clear();
-    write!(&mut temp_buf, "{:.0}{}", high, temp_unit).unwrap();
+    write!(
+        &mut temp_buf,
+        "{:.0}{}",
+        OrDashes(high.map(|high| high.value)),
+        unit.symbol()
+    )
+    .unwrap();
     draw_text_xy_wh(&temp_buf, 140, 60, 80, 0, buffer)?;
     log::info!("High temp drawn successfully");
 
     Ok(())
 }
 
-fn draw_today_wind<D>(wind_speed: f32, wind_dir: i32, wind_unit: &str, buffer: &mut D) -> Result<()>
+fn draw_today_wind<D>(
+    wind_speed: Option<Speed>,
+    wind_dir: i32,
+    max_wind_speed: Option<Speed>,
+    buffer: &mut D,
+) -> Result<()>
 where
     D: DrawTarget<Color = Gray2> + OriginDimensions,
     <D as DrawTarget>::Error: core::fmt::Debug,
 {
     let mut wind_buf: String<24> = String::new();
 
-    // Draw the wind speed + direction
+    // Draw the current wind speed + direction with today's maximum
     let wind_dir = wind_dir_text(wind_dir);
     wind_buf.clear();
-    write!(&mut wind_buf, "{}{} {}", wind_speed, wind_unit, wind_dir).unwrap();
+    write!(
+        &mut wind_buf,
+        "{:.0}/{:.0}{} {}",
+        OrDashes(wind_speed.map(|speed| speed.value)),
+        OrDashes(max_wind_speed.map(|max| max.value)),
+        display_units().wind_speed.symbol(),
+        wind_dir
+    )
+    .unwrap();
     draw_text_xy_wh(&wind_buf, 95, 90, 80, 0, buffer)?;
     log::info!("Windspeed drawn successfully");
 
     Ok(())
 }
 
-fn draw_today_weather_icon<D>(weather_code: i32, buffer: &mut D) -> Result<()>
+fn draw_today_precipitation<D>(
+    probability: Option<i32>,
+    sum: Option<Length>,
+    rain: Option<Length>,
+    snow: Option<Length>,
+    hours: Option<f32>,
+    buffer: &mut D,
+) -> Result<()>
 where
     D: DrawTarget<Color = Gray2> + OriginDimensions,
     <D as DrawTarget>::Error: core::fmt::Debug,
 {
-    let icon = weather_code_to_icon_index(weather_code);
-    draw_weather_icon(icon, Point::new(6, 40), 70, buffer)?;
+    let unit = display_units().precipitation;
+    let mut precip_buf: String<24> = String::new();
+    let precision = precip_precision(unit);
+    let value = |amount: Option<Length>| OrDashes(amount.map(|amount| amount.value));
+
+    // Draw the chance of precipitation and the expected total
+    write!(
+        &mut precip_buf,
+        "{}% {:.*}{}",
+        OrDashes(probability),
+        precision,
+        value(sum),
+        unit.symbol()
+    )
+    .unwrap();
+    draw_text_xy_wh(&precip_buf, 95, 70, 85, 0, buffer)?;
+    log::info!("Precipitation drawn successfully");
+
+    // Draw the hours of precipitation with the rain and snow breakdown
+    precip_buf.clear();
+    write!(
+        &mut precip_buf,
+        "{:.0}h R{:.*} S{:.*}",
+        OrDashes(hours),
+        precision,
+        value(rain),
+        precision,
+        value(snow)
+    )
+    .unwrap();
+    draw_text_xy_wh(&precip_buf, 95, 80, 85, 0, buffer)?;
+    log::info!("Rain and snowfall drawn successfully");
+
+    Ok(())
+}
+
+fn draw_today_weather_icon<D>(weather_code: WeatherCode, is_day: bool, buffer: &mut D) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    draw_weather_code_icon(weather_code, is_day, Point::new(6, 40), 70, buffer)?;
     log::info!("Today weather icon drawn successfully");
     Ok(())
 }
@@ -180,71 +530,405 @@ where
     Ok(())
 }
 
-/// Draw the future weather view onto the display buffer
-fn draw_future_weather_view<D>(weather_data: &OpenMeteoResponse, buffer: &mut D) -> Result<()>
+/// Today's sunrise and sunset from the forecast, or computed locally when the forecast
+/// omits them
+fn today_sunrise_sunset(weather_data: &ValidatedForecast) -> (Option<i64>, Option<i64>) {
+    let (ts, offset) = (weather_data.current.time, weather_data.utc_offset_seconds);
+    let (latitude, longitude) = (weather_data.latitude, weather_data.longitude);
+    if let Some((dawn, dusk)) = civil_twilight_unix(ts, offset, latitude, longitude) {
+        log::info!(
+            "Civil twilight {:?} to {:?}, day length {} min",
+            unix_hh_mm(dawn, offset),
+            unix_hh_mm(dusk, offset),
+            day_length_secs(ts, offset, latitude, longitude) / 60
+        );
+    }
+    let today = weather_data.today();
+    match (today.sunrise, today.sunset) {
+        (Some(sunrise), Some(sunset)) => (Some(sunrise), Some(sunset)),
+        _ => {
+            log::warn!("Forecast has no sunrise/sunset, computing them locally");
+            sunrise_sunset_unix(ts, offset, latitude, longitude).unzip()
+        }
+    }
+}
+
+/// Draw the UV index category and the hours of sunshine onto the display buffer
+fn draw_today_uv_sunshine<D>(
+    uv_index: Option<f32>,
+    sunshine_secs: Option<f32>,
+    buffer: &mut D,
+) -> Result<()>
 where
     D: DrawTarget<Color = Gray2> + OriginDimensions,
     <D as DrawTarget>::Error: core::fmt::Debug,
 {
-    let days = weather_data.daily.time.len();
-    let temp_unit = &weather_data
-        .daily_units
-        .temperature_2m_max
-        .chars()
-        .last()
-        .unwrap();
+    let mut uv_buf: String<16> = String::new();
+    // a non-finite reading has no category, show it as missing
+    let uv_index = uv_index.filter(|uv| uv.is_finite());
+    let sunshine_secs = sunshine_secs.filter(|secs| secs.is_finite());
 
-    let mut temp_buf: String<8> = String::new();
+    // Draw the UV index with its category
+    write!(
+        &mut uv_buf,
+        "UV {:.0} {}",
+        OrDashes(uv_index),
+        uv_index.map_or("", uv_index_text)
+    )
+    .unwrap();
+    draw_text_xy_wh(&uv_buf, 95, 101, 85, 0, buffer)?;
+    log::info!("UV index drawn successfully");
 
-    // Draw the day of week, weather icon, the min and max temp for each future day
-    for i in 1..days {
-        let start_point = Point::new(191, 15 + ((i as i32 - 1) * 18));
+    // Draw the sunshine duration beside sunrise/sunset
+    uv_buf.clear();
+    write!(
+        &mut uv_buf,
+        "{:.1}h",
+        OrDashes(sunshine_secs.map(|secs| secs / 3600.0))
+    )
+    .unwrap();
+    draw_text_xy_wh(&uv_buf, 148, 113, 32, 0, buffer)?;
+    log::info!("Sunshine duration drawn successfully");
 
-        // day of week
-        let ts = *weather_data.daily.time.get(i).unwrap();
-        let dow = short_dow_unix(ts, weather_data.utc_offset_seconds).unwrap();
-        draw_text(
+    Ok(())
+}
+
+/// Draw the moon phase glyph and illuminated percentage onto the display buffer
+fn draw_today_moon<D>(moon: &MoonInfo, latitude: f32, buffer: &mut D) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    // The lit side is mirrored for observers in the southern hemisphere
+    draw_moon_glyph(
+        moon.elongation,
+        latitude < 0.0,
+        Point::new(66, 117),
+        9,
+        buffer,
+    )?;
+
+    let mut moon_buf: String<5> = String::new();
+    write!(&mut moon_buf, "{:.0}%", moon.illumination * 100.0).unwrap();
+    draw_text_xy_wh(&moon_buf, 73, 113, 22, 0, buffer)?;
+
+    log::info!(
+        "Moon drawn successfully: {} (age {:.1} days)",
+        moon.phase.name(),
+        moon.age_days()
+    );
+    Ok(())
+}
+
+/// Draw the future weather view onto the display buffer.
+///
+/// When there are more future days than `rows`, the last row sums up the days that do not fit.
+fn draw_future_weather_view<D>(
+    weather_data: &ValidatedForecast,
+    rows: usize,
+    buffer: &mut D,
+) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    let display_unit = display_units().temperature;
+    // Shown as "--" when the unit is not recognized
+    let temp_unit = weather_data.daily_units.temperature();
+    let temperature =
+        |value: Option<f32>| Some(Temperature::new(value?, temp_unit?).to(display_unit).value);
+
+    // The rows follow the today icon, with moon variants at night
+    let is_day = weather_data.current.is_day != 0;
+    let future_days = ((TODAY + 1)..).map_while(|i| weather_data.daily.day(i));
+    let days = weather_data.daily.time.len().saturating_sub(TODAY + 1);
+    let single_rows = if days > rows { rows - 1 } else { rows };
+
+    // Draw the day of week, weather icon, the min/max temp and chance of precipitation for
+    // each future day
+    for (row, day) in future_days.clone().take(single_rows).enumerate() {
+        let dow = short_dow_unix(day.time, weather_data.utc_offset_seconds).unwrap_or("");
+        draw_future_row(
             dow,
-            start_point + Point::new(0, 5),
-            Size::new(20, 0),
+            day.weather_code,
+            is_day,
+            (
+                temperature(day.temperature_2m_min),
+                temperature(day.temperature_2m_max),
+            ),
+            day.precipitation_probability_max,
+            future_row_origin(row),
+            buffer,
+        )?;
+        log::info!("Future day {} drawn successfully", row + 1);
+    }
+
+    if days > rows {
+        // The worst weather, the full temperature range and the highest chance of
+        // precipitation of the remaining days, labelled with how many there are
+        let rest = future_days.skip(single_rows);
+        let mut label: String<4> = String::new();
+        write!(&mut label, "+{}", days - single_rows).unwrap();
+        let code = rest
+            .clone()
+            .filter_map(|day| day.weather_code)
+            .max_by_key(|code| code.severity());
+        let min = rest
+            .clone()
+            .filter_map(|day| day.temperature_2m_min)
+            .reduce(f32::min);
+        let max = rest
+            .clone()
+            .filter_map(|day| day.temperature_2m_max)
+            .reduce(f32::max);
+        let probability = rest
+            .filter_map(|day| day.precipitation_probability_max)
+            .max();
+        draw_future_row(
+            &label,
+            code,
+            is_day,
+            (temperature(min), temperature(max)),
+            probability,
+            future_row_origin(single_rows),
             buffer,
         )?;
+        log::info!(
+            "Remaining {} future days drawn successfully",
+            days - single_rows
+        );
+    }
+    Ok(())
+}
 
-        // weather icon
-        let icon = weather_code_to_icon_index(*weather_data.daily.weather_code.get(i).unwrap());
-        draw_weather_icon(icon, start_point + Point::new(20, 0), 20, buffer)?;
+/// Draw one row of the forecast panel at `start_point`, with temperatures in the display unit
+fn draw_future_row<D>(
+    label: &str,
+    code: Option<WeatherCode>,
+    is_day: bool,
+    (min, max): (Option<f32>, Option<f32>),
+    probability: Option<i32>,
+    start_point: Point,
+    buffer: &mut D,
+) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    // day of week
+    draw_text(
+        label,
+        start_point + Point::new(0, 5),
+        Size::new(20, 0),
+        buffer,
+    )?;
 
-        // minimum temperature
-        temp_buf.clear();
-        write!(
-            &mut temp_buf,
-            "{:.0}{}",
-            weather_data.daily.temperature_2m_min[i], temp_unit
-        )
-        .unwrap();
+    // weather icon
+    if let Some(code) = code {
+        draw_weather_code_icon(code, is_day, start_point + Point::new(20, 0), 20, buffer)?;
+    }
+
+    // minimum and maximum temperature
+    let mut temp_buf: String<12> = String::new();
+    write!(&mut temp_buf, "{:.0}/{:.0}", OrDashes(min), OrDashes(max)).unwrap();
+    draw_text(
+        &temp_buf,
+        start_point + Point::new(43, 5),
+        Size::new(36, 0),
+        buffer,
+    )?;
+
+    // chance of precipitation
+    temp_buf.clear();
+    write!(&mut temp_buf, "{}%", OrDashes(probability)).unwrap();
+    draw_text(
+        &temp_buf,
+        start_point + Point::new(80, 5),
+        Size::new(25, 0),
+        buffer,
+    )
+}
+
+/// Top left corner of row `row` of the forecast panel
+fn future_row_origin(row: usize) -> Point {
+    Point::new(191, 15 + row as i32 * 18)
+}
+
+/// Draw the pressure tendency arrow and the sea level pressure beside today's date
+fn draw_today_pressure_trend<D>(trend: &PressureTrend, buffer: &mut D) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    draw_trend_arrow(trend.tendency, Point::new(101, 21), buffer)?;
+
+    let mut pressure_buf: String<12> = String::new();
+    let pressure = trend.pressure().to(display_units().pressure);
+    let precision = match pressure.unit {
+        PressureUnit::InchOfMercury => 2,
+        PressureUnit::Hectopascal => 0,
+    };
+    write!(
+        &mut pressure_buf,
+        "{:.*}{}",
+        precision,
+        pressure.value,
+        pressure.unit.symbol()
+    )
+    .unwrap();
+    draw_text_xy_wh(&pressure_buf, 109, 16, 71, 0, buffer)?;
+
+    log::info!(
+        "Pressure trend drawn successfully: {:.1} hPa {}, {:+.1} hPa/3h",
+        trend.pressure_hpa,
+        trend.tendency.name(),
+        trend.change_hpa
+    );
+    Ok(())
+}
+
+/// Draw one row per saved location, with today's outlook and the current temperature, into
+/// `buffer` using `report`.
+///
+/// Returns `Ok(())` on success or an error `Result` on failure.
+pub fn draw_location_summary_view<D>(report: &SummaryReport, buffer: &mut D) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    if let Some(first) = report.summary.locations.first()
+        && let Some(date) = first
+            .daily
+            .time
+            .first()
+            .and_then(|&ts| format_date_unix(ts, first.utc_offset_seconds))
+    {
+        draw_text_xy_wh(&date, 4, 2, 170, 0, buffer)?;
+    }
+    draw_text_xy_wh("Now", 180, 2, 36, 0, buffer)?;
+    draw_text_xy_wh("Lo/Hi", 216, 2, 42, 0, buffer)?;
+    draw_text_xy_wh("Prec", 262, 2, 30, 0, buffer)?;
+    log::info!("Summary header drawn successfully");
+
+    for (i, (location, summary)) in report
+        .locations
+        .iter()
+        .zip(report.summary.locations.iter())
+        .enumerate()
+    {
+        let start_point = Point::new(4, 17 + (i as i32 * 22));
+        draw_summary_row(&location.name, summary, start_point, buffer)?;
+        log::info!("Summary row {} drawn successfully", i);
+    }
+
+    // The stale banner takes the place of the header
+    if let Some(error) = &report.fetch_error
+        && let Some(first) = report.summary.locations.first()
+    {
+        draw_stale_banner(first.current.time, first.utc_offset_seconds, error, buffer)?;
+    }
+    Ok(())
+}
+
+/// Draw the icon, name, current temperature, min/max temperature and chance of precipitation
+/// of one location at `start_point`
+fn draw_summary_row<D>(
+    name: &str,
+    summary: &LocationSummary,
+    start_point: Point,
+    buffer: &mut D,
+) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    let daily = &summary.daily;
+    if let Some(code) = daily.weather_code.first().copied().flatten() {
+        let is_day = summary.current.is_day != 0;
+        draw_weather_code_icon(code, is_day, start_point, 20, buffer)?;
+    }
+
+    // Keep the name on a single line
+    let mut text_buf: String<25> = String::new();
+    if name.is_empty() {
+        let _ = write!(
+            &mut text_buf,
+            "({:.2}, {:.2})",
+            summary.latitude, summary.longitude
+        );
+    } else {
+        for c in name.chars() {
+            if text_buf.push(c).is_err() {
+                break;
+            }
+        }
+    }
+    draw_text(
+        &text_buf,
+        start_point + Point::new(24, 6),
+        Size::new(150, 0),
+        buffer,
+    )?;
+
+    text_buf.clear();
+    // Temperatures in units that are not recognized are shown as "--"
+    let display_unit = display_units().temperature;
+    let temperature = summary.current_units.temperature().map(|unit| {
+        Temperature::new(summary.current.temperature_2m, unit)
+            .to(display_unit)
+            .value
+    });
+    let _ = write!(
+        &mut text_buf,
+        "{:.0}{}",
+        OrDashes(temperature),
+        display_unit.symbol()
+    );
+    draw_text(
+        &text_buf,
+        start_point + Point::new(176, 6),
+        Size::new(36, 0),
+        buffer,
+    )?;
+
+    // Today's values, shown as "--" when the API has none
+    if !daily.time.is_empty() {
+        text_buf.clear();
+        let temp_unit = summary.daily_units.temperature();
+        let temperature = |values: &[Option<f32>]| {
+            let value = values.first().copied().flatten();
+            OrDashes(
+                value
+                    .zip(temp_unit)
+                    .map(|(value, unit)| Temperature::new(value, unit).to(display_unit).value),
+            )
+        };
+        let _ = write!(
+            &mut text_buf,
+            "{:.0}/{:.0}",
+            temperature(&daily.temperature_2m_min),
+            temperature(&daily.temperature_2m_max)
+        );
         draw_text(
-            &temp_buf,
-            start_point + Point::new(45, 5),
-            Size::new(30, 6),
+            &text_buf,
+            start_point + Point::new(212, 6),
+            Size::new(42, 0),
             buffer,
         )?;
 
-        // maximum temperature
-        temp_buf.clear();
-        write!(
-            &mut temp_buf,
-            "{:.0}{}",
-            weather_data.daily.temperature_2m_max[i], temp_unit
-        )
-        .unwrap();
+        text_buf.clear();
+        let probability = daily
+            .precipitation_probability_max
+            .first()
+            .copied()
+            .flatten();
+        let _ = write!(&mut text_buf, "{}%", OrDashes(probability));
         draw_text(
-            &temp_buf,
-            start_point + Point::new(75, 5),
+            &text_buf,
+            start_point + Point::new(258, 6),
             Size::new(30, 0),
             buffer,
         )?;
-
-        log::info!("Future day {} drawn successfully", i);
     }
     Ok(())
 }
@@ -261,6 +945,89 @@ where
     Ok(())
 }
 
+/// Draw the moon disk centred on `center`, lit according to `elongation` in degrees
+/// (0 new, 180 full), with the waxing side on the right unless `mirrored`
+fn draw_moon_glyph<D>(
+    elongation: f32,
+    mirrored: bool,
+    center: Point,
+    diameter: u32,
+    buffer: &mut D,
+) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2>,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    let radius = diameter as f32 / 2.0;
+    let terminator = cosf(elongation.to_radians());
+    let waxing = elongation < 180.0;
+
+    // Per row, the lit span runs between the limb and the terminator ellipse
+    let rows = (-(diameter as i32) / 2)..=(diameter as i32 / 2);
+    let pixels = rows.flat_map(|dy| {
+        let half_width = sqrtf((radius * radius - (dy * dy) as f32).max(0.0));
+        let (lit_from, lit_to) = if waxing {
+            (half_width * terminator, half_width)
+        } else {
+            (-half_width, -half_width * terminator)
+        };
+        let span = half_width as i32;
+        (-span..=span).map(move |dx| {
+            let x = if mirrored { -dx } else { dx } as f32;
+            let color = if x >= lit_from && x <= lit_to {
+                Gray2::WHITE
+            } else {
+                Gray2::new(1)
+            };
+            Pixel(center + Point::new(dx, dy), color)
+        })
+    });
+    buffer.draw_iter(pixels).map_err(|e| {
+        log::error!("Failed to draw moon glyph to display buffer: {:?}", e);
+        AppError::GraphicsError
+    })?;
+
+    Circle::with_center(center, diameter + 2)
+        .into_styled(PrimitiveStyle::with_stroke(Gray2::BLACK, 1))
+        .draw(buffer)
+        .map_err(|e| {
+            log::error!("Failed to draw moon outline to display buffer: {:?}", e);
+            AppError::GraphicsError
+        })
+}
+
+/// Draw an arrow centred on `center` pointing up for rising, right for steady and down for
+/// falling pressure
+fn draw_trend_arrow<D>(tendency: PressureTendency, center: Point, buffer: &mut D) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2>,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    // Tip and the two barb ends for an arrow pointing right, rotated for the tendency
+    let rotate = |p: Point| match tendency {
+        PressureTendency::Rising => Point::new(p.y, -p.x),
+        PressureTendency::Steady => p,
+        PressureTendency::Falling => Point::new(-p.y, p.x),
+    };
+    let tip = rotate(Point::new(5, 0));
+    let lines = [
+        (rotate(Point::new(-5, 0)), tip),
+        (rotate(Point::new(1, -4)), tip),
+        (rotate(Point::new(1, 4)), tip),
+    ];
+    let style = PrimitiveStyle::with_stroke(Gray2::BLACK, 2);
+    for (start, end) in lines {
+        Line::new(center + start, center + end)
+            .into_styled(style)
+            .draw(buffer)
+            .map_err(|e| {
+                log::error!("Failed to draw trend arrow to display buffer: {:?}", e);
+                AppError::GraphicsError
+            })?;
+    }
+    Ok(())
+}
+
 /// Draw a weather icon from a sprite sheet onto the display
 fn draw_weather_icon<D>(icon_index: i32, position: Point, size: u32, buffer: &mut D) -> Result<()>
 where
@@ -296,20 +1063,144 @@ where
     draw_image(&sub_image, position, buffer)
 }
 
-/// Map weather codes to icon indices in the sprite sheet (3x3 grid, row-major order)
-fn weather_code_to_icon_index(code: i32) -> i32 {
-    log::info!("weather code: {}", code);
-    match code {
-        0 => 0,                                               // sunny
-        1 => 1,                                               // mainly clear
-        2 => 2,                                               // partly sunny/cloudy
-        3 => 3,                                               // overcast
-        61 | 63 | 65 => 4,                                    // rain
-        51 | 53 | 55 | 80 | 81 | 82 => 5,                     // drizzle
-        95 | 96 | 99 => 6,                                    // storms
-        56 | 57 | 66 | 67 | 71 | 73 | 75 | 77 | 85 | 86 => 7, // snow
-        45 | 48 => 8,                                         // fog
-        _ => 0,                                               // default to sunny
+/// Draw the icon of weather `code` at `position`, or a placeholder for codes without one.
+/// `is_day` selects the moon variants for clear and partly cloudy skies.
+fn draw_weather_code_icon<D>(
+    code: WeatherCode,
+    is_day: bool,
+    position: Point,
+    size: u32,
+    buffer: &mut D,
+) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    log::info!(
+        "weather code: {:?} \"{}\", severity {:?} (day: {})",
+        code,
+        code.description(),
+        code.severity(),
+        is_day
+    );
+    match code.icon(is_day) {
+        Some(icon) => draw_weather_icon(weather_icon_index(icon), position, size, buffer),
+        None => draw_unknown_weather_icon(code, position, size, buffer),
+    }
+}
+
+/// Draw a framed question mark in place of the icon of an unknown weather code, followed by
+/// the raw code on the large icon
+fn draw_unknown_weather_icon<D>(
+    code: WeatherCode,
+    position: Point,
+    size: u32,
+    buffer: &mut D,
+) -> Result<()>
+where
+    D: DrawTarget<Color = Gray2> + OriginDimensions,
+    <D as DrawTarget>::Error: core::fmt::Debug,
+{
+    Rectangle::new(position + Point::new(1, 1), Size::new(size - 2, size - 2))
+        .into_styled(PrimitiveStyle::with_stroke(Gray2::BLACK, 2))
+        .draw(buffer)
+        .map_err(|e| {
+            log::error!(
+                "Failed to draw unknown weather icon to display buffer: {:?}",
+                e
+            );
+            AppError::GraphicsError
+        })?;
+
+    let mut label: String<12> = String::new();
+    let _ = label.push('?');
+    if let (70, WeatherCode::Unknown(raw)) = (size, code) {
+        let _ = write!(&mut label, " {}", raw);
+    }
+    // center the label, 6x10 px per character
+    let offset = Point::new(
+        (size as i32 - 6 * label.len() as i32) / 2,
+        (size as i32 - 10) / 2,
+    );
+    draw_text(&label, position + offset, Size::new(size, 0), buffer)
+}
+
+/// Index of `icon` in the sprite sheets (3x4 grid, row-major order)
+fn weather_icon_index(icon: WeatherIcon) -> i32 {
+    match icon {
+        WeatherIcon::Sunny => 0,
+        WeatherIcon::MainlyClear => 1,
+        WeatherIcon::PartlyCloudy => 2,
+        WeatherIcon::Overcast => 3,
+        WeatherIcon::Rain => 4,
+        WeatherIcon::Drizzle => 5,
+        WeatherIcon::Storms => 6,
+        WeatherIcon::Snow => 7,
+        WeatherIcon::Fog => 8,
+        WeatherIcon::ClearNight => 9,
+        WeatherIcon::MainlyClearNight => 10,
+        WeatherIcon::PartlyCloudyNight => 11,
+    }
+}
+
+/// Short label for the kind of error behind a stale forecast
+fn error_badge_text(error: &AppError) -> &'static str {
+    match error {
+        AppError::DnsQueryFailed
+        | AppError::ConnectionFailed
+        | AppError::LinkFailed
+        | AppError::DhcpTimeout
+        | AppError::HttpRequestFailed
+        | AppError::SocketReadError
+        | AppError::ResponseTooLarge
+        | AppError::RequestTimeout => "NET",
+        AppError::JsonParseFailed | AppError::ApiError(_) | AppError::InvalidForecast(_) => "API",
+        AppError::LocationNotFound => "LOC",
+        _ => "ERR",
+    }
+}
+
+/// Map a UV index to its WHO exposure category
+fn uv_index_text(uv_index: f32) -> &'static str {
+    // categories are defined on the index rounded to a whole number
+    match uv_index + 0.5 {
+        ..3.0 => "Low",
+        3.0..6.0 => "Moderate",
+        6.0..8.0 => "High",
+        8.0..11.0 => "Very High",
+        _ => "Extreme",
+    }
+}
+
+/// Map a US AQI value to its EPA category
+fn us_aqi_text(aqi: i32) -> &'static str {
+    match aqi {
+        ..=50 => "Good",
+        51..=100 => "Moderate",
+        101..=150 => "USG", // unhealthy for sensitive groups
+        151..=200 => "Unhealthy",
+        201..=300 => "Unhealthy+",
+        _ => "Hazardous",
+    }
+}
+
+/// Map a European AQI value to its EEA category
+fn european_aqi_text(aqi: i32) -> &'static str {
+    match aqi {
+        ..=20 => "Good",
+        21..=40 => "Fair",
+        41..=60 => "Moderate",
+        61..=80 => "Poor",
+        81..=100 => "Very Poor",
+        _ => "Extr. Poor",
+    }
+}
+
+/// Number of decimal places to show for a precipitation amount in `unit`
+fn precip_precision(unit: LengthUnit) -> usize {
+    match unit {
+        LengthUnit::Inch => 2,
+        LengthUnit::Millimetre | LengthUnit::Centimetre => 1,
     }
 }
 
@@ -0,0 +1,408 @@
+use core::ops::{Deref, RangeInclusive};
+
+use crate::{
+    comfort::ComfortMetrics,
+    units::{
+        Length, LengthUnit, Pressure, PressureUnit, Speed, SpeedUnit, Temperature, TemperatureUnit,
+    },
+    weather::model::{DayForecast, OpenMeteoResponse, TODAY},
+};
+
+// Plausible ranges, wider than the recorded extremes
+const TEMPERATURE_C: RangeInclusive<f32> = -95.0..=65.0;
+const APPARENT_TEMPERATURE_C: RangeInclusive<f32> = -110.0..=80.0;
+const WIND_SPEED_MS: RangeInclusive<f32> = 0.0..=120.0;
+/// Daily precipitation or snowfall
+const AMOUNT_MM: RangeInclusive<f32> = 0.0..=5000.0;
+const PRESSURE_MSL_HPA: RangeInclusive<f32> = 850.0..=1090.0;
+const SURFACE_PRESSURE_HPA: RangeInclusive<f32> = 300.0..=1090.0;
+const UV_INDEX: RangeInclusive<f32> = 0.0..=25.0;
+const HOURS_PER_DAY: RangeInclusive<f32> = 0.0..=24.0;
+const SECS_PER_DAY: RangeInclusive<f32> = 0.0..=86_400.0;
+const PERCENT: RangeInclusive<i32> = 0..=100;
+const DEGREES: RangeInclusive<i32> = 0..=360;
+
+/// 2020-01-01, earlier timestamps mean a broken response
+const MIN_TIMESTAMP: i64 = 1_577_836_800;
+const DAY_SECS: i64 = 86_400;
+const HOUR_SECS: i64 = 3_600;
+
+/// Reason a parsed forecast is not shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ForecastError {
+    #[error("forecast has {0} days, today is missing")]
+    MissingToday(usize),
+
+    #[error("{field} has {len} entries instead of {expected}")]
+    LengthMismatch {
+        field: &'static str,
+        len: usize,
+        expected: usize,
+    },
+
+    #[error("{0} timestamps out of range")]
+    TimestampOutOfRange(&'static str),
+
+    #[error("{0} unit not recognized")]
+    UnknownUnit(&'static str),
+
+    #[error("{0} value out of range")]
+    ImplausibleValue(&'static str),
+}
+
+/// A forecast that passed validation: every series has one entry per timestamp, the
+/// timestamps are consecutive days and hours around the current time, and all values are
+/// plausible. Values may still be missing (`null`), and so may sunrise and sunset.
+#[derive(Debug)]
+pub struct ValidatedForecast {
+    forecast: OpenMeteoResponse,
+    today: DayForecast,
+}
+
+impl ValidatedForecast {
+    pub fn today(&self) -> &DayForecast {
+        &self.today
+    }
+
+    /// Dew point, heat index, wind chill and comfort class of the current conditions, computed
+    /// on the device whether or not the apparent temperature was supplied
+    pub fn comfort(&self) -> Option<ComfortMetrics> {
+        let (current, units) = (&self.current, &self.current_units);
+        Some(ComfortMetrics::new(
+            Temperature::new(current.temperature_2m, units.temperature()?),
+            current.relative_humidity_2m as f32,
+            Speed::new(current.wind_speed_10m, units.wind_speed()?),
+        ))
+    }
+}
+
+impl Deref for ValidatedForecast {
+    type Target = OpenMeteoResponse;
+
+    fn deref(&self) -> &OpenMeteoResponse {
+        &self.forecast
+    }
+}
+
+/// Validate a parsed forecast before it reaches the UI
+impl TryFrom<OpenMeteoResponse> for ValidatedForecast {
+    type Error = ForecastError;
+
+    fn try_from(forecast: OpenMeteoResponse) -> Result<Self, Self::Error> {
+        check_daily(&forecast)?;
+        check_hourly(&forecast)?;
+        check_current(&forecast)?;
+
+        let today = forecast
+            .daily
+            .day(TODAY)
+            .ok_or(ForecastError::MissingToday(forecast.daily.time.len()))?;
+        Ok(ValidatedForecast { forecast, today })
+    }
+}
+
+fn check_daily(forecast: &OpenMeteoResponse) -> Result<(), ForecastError> {
+    let (daily, units) = (&forecast.daily, &forecast.daily_units);
+    let days = daily.time.len();
+    if days <= TODAY {
+        return Err(ForecastError::MissingToday(days));
+    }
+
+    check_len("daily.weather_code", daily.weather_code.len(), days)?;
+    check_len(
+        "daily.temperature_2m_max",
+        daily.temperature_2m_max.len(),
+        days,
+    )?;
+    check_len(
+        "daily.temperature_2m_min",
+        daily.temperature_2m_min.len(),
+        days,
+    )?;
+    check_len(
+        "daily.wind_speed_10m_max",
+        daily.wind_speed_10m_max.len(),
+        days,
+    )?;
+    check_len(
+        "daily.wind_direction_10m_dominant",
+        daily.wind_direction_10m_dominant.len(),
+        days,
+    )?;
+    check_len(
+        "daily.precipitation_sum",
+        daily.precipitation_sum.len(),
+        days,
+    )?;
+    check_len(
+        "daily.precipitation_probability_max",
+        daily.precipitation_probability_max.len(),
+        days,
+    )?;
+    check_len("daily.rain_sum", daily.rain_sum.len(), days)?;
+    check_len("daily.snowfall_sum", daily.snowfall_sum.len(), days)?;
+    check_len(
+        "daily.precipitation_hours",
+        daily.precipitation_hours.len(),
+        days,
+    )?;
+    check_len("daily.uv_index_max", daily.uv_index_max.len(), days)?;
+    check_len(
+        "daily.sunshine_duration",
+        daily.sunshine_duration.len(),
+        days,
+    )?;
+
+    // Local midnights, 23 or 25 hours apart across daylight saving changes
+    check_steps(
+        "daily.time",
+        &daily.time,
+        DAY_SECS - HOUR_SECS..=DAY_SECS + HOUR_SECS,
+    )?;
+
+    let temp_unit = units
+        .temperature()
+        .ok_or(ForecastError::UnknownUnit("daily.temperature_2m_max"))?;
+    let celsius = |&value: &f32| {
+        Temperature::new(value, temp_unit)
+            .to(TemperatureUnit::Celsius)
+            .value
+    };
+    check_range(
+        "daily.temperature_2m_max",
+        daily.temperature_2m_max.iter().flatten().map(celsius),
+        &TEMPERATURE_C,
+    )?;
+    check_range(
+        "daily.temperature_2m_min",
+        daily.temperature_2m_min.iter().flatten().map(celsius),
+        &TEMPERATURE_C,
+    )?;
+    if daily
+        .temperature_2m_min
+        .iter()
+        .zip(&daily.temperature_2m_max)
+        .any(|pair| matches!(pair, (Some(min), Some(max)) if min > max))
+    {
+        return Err(ForecastError::ImplausibleValue("daily.temperature_2m_min"));
+    }
+
+    let wind_unit = units
+        .wind_speed()
+        .ok_or(ForecastError::UnknownUnit("daily.wind_speed_10m_max"))?;
+    check_range(
+        "daily.wind_speed_10m_max",
+        daily
+            .wind_speed_10m_max
+            .iter()
+            .flatten()
+            .map(|&value| metres_per_second(Speed::new(value, wind_unit))),
+        &WIND_SPEED_MS,
+    )?;
+    check_range(
+        "daily.wind_direction_10m_dominant",
+        daily.wind_direction_10m_dominant.iter().flatten().copied(),
+        &DEGREES,
+    )?;
+
+    let precip_unit = units
+        .precipitation()
+        .ok_or(ForecastError::UnknownUnit("daily.precipitation_sum"))?;
+    let snowfall_unit = units
+        .snowfall()
+        .ok_or(ForecastError::UnknownUnit("daily.snowfall_sum"))?;
+    for (field, values, unit) in [
+        (
+            "daily.precipitation_sum",
+            &daily.precipitation_sum,
+            precip_unit,
+        ),
+        ("daily.rain_sum", &daily.rain_sum, precip_unit),
+        ("daily.snowfall_sum", &daily.snowfall_sum, snowfall_unit),
+    ] {
+        check_range(
+            field,
+            values
+                .iter()
+                .flatten()
+                .map(|&value| millimetres(Length::new(value, unit))),
+            &AMOUNT_MM,
+        )?;
+    }
+    check_range(
+        "daily.precipitation_probability_max",
+        daily
+            .precipitation_probability_max
+            .iter()
+            .flatten()
+            .copied(),
+        &PERCENT,
+    )?;
+    check_range(
+        "daily.precipitation_hours",
+        daily.precipitation_hours.iter().flatten().copied(),
+        &HOURS_PER_DAY,
+    )?;
+    check_range(
+        "daily.uv_index_max",
+        daily.uv_index_max.iter().flatten().copied(),
+        &UV_INDEX,
+    )?;
+    check_range(
+        "daily.sunshine_duration",
+        daily.sunshine_duration.iter().flatten().copied(),
+        &SECS_PER_DAY,
+    )
+}
+
+fn check_hourly(forecast: &OpenMeteoResponse) -> Result<(), ForecastError> {
+    let hourly = &forecast.hourly;
+    let hours = hourly.time.len();
+    check_len("hourly.temperature_2m", hourly.temperature_2m.len(), hours)?;
+    check_len(
+        "hourly.precipitation_probability",
+        hourly.precipitation_probability.len(),
+        hours,
+    )?;
+    check_len("hourly.weather_code", hourly.weather_code.len(), hours)?;
+    check_len("hourly.wind_speed_10m", hourly.wind_speed_10m.len(), hours)?;
+    check_len(
+        "hourly.wind_direction_10m",
+        hourly.wind_direction_10m.len(),
+        hours,
+    )?;
+    check_len("hourly.pressure_msl", hourly.pressure_msl.len(), hours)?;
+
+    check_steps("hourly.time", &hourly.time, HOUR_SECS..=HOUR_SECS)?;
+
+    let pressure_unit = PressureUnit::parse(&forecast.hourly_units.pressure_msl)
+        .ok_or(ForecastError::UnknownUnit("hourly.pressure_msl"))?;
+    check_range(
+        "hourly.pressure_msl",
+        hourly
+            .pressure_msl
+            .iter()
+            .flatten()
+            .map(|&value| hectopascals(Pressure::new(value, pressure_unit))),
+        &PRESSURE_MSL_HPA,
+    )?;
+    check_range(
+        "hourly.precipitation_probability",
+        hourly.precipitation_probability.iter().flatten().copied(),
+        &PERCENT,
+    )
+}
+
+fn check_current(forecast: &OpenMeteoResponse) -> Result<(), ForecastError> {
+    let (current, units) = (&forecast.current, &forecast.current_units);
+
+    // The current time falls on today, which starts at local midnight
+    let today = forecast.daily.time[TODAY];
+    if !(today..today + DAY_SECS + HOUR_SECS).contains(&current.time) {
+        return Err(ForecastError::TimestampOutOfRange("current.time"));
+    }
+
+    let temp_unit = units
+        .temperature()
+        .ok_or(ForecastError::UnknownUnit("current.temperature_2m"))?;
+    let celsius = |value: f32| {
+        Temperature::new(value, temp_unit)
+            .to(TemperatureUnit::Celsius)
+            .value
+    };
+    check_range(
+        "current.temperature_2m",
+        [celsius(current.temperature_2m)],
+        &TEMPERATURE_C,
+    )?;
+    check_range(
+        "current.apparent_temperature",
+        current.apparent_temperature.map(celsius),
+        &APPARENT_TEMPERATURE_C,
+    )?;
+    check_range(
+        "current.relative_humidity_2m",
+        [current.relative_humidity_2m],
+        &PERCENT,
+    )?;
+
+    let wind_unit = units
+        .wind_speed()
+        .ok_or(ForecastError::UnknownUnit("current.wind_speed_10m"))?;
+    check_range(
+        "current.wind_speed_10m",
+        [metres_per_second(Speed::new(
+            current.wind_speed_10m,
+            wind_unit,
+        ))],
+        &WIND_SPEED_MS,
+    )?;
+    check_range(
+        "current.wind_direction_10m",
+        [current.wind_direction_10m],
+        &DEGREES,
+    )?;
+
+    let pressure_unit = units
+        .pressure()
+        .ok_or(ForecastError::UnknownUnit("current.surface_pressure"))?;
+    check_range(
+        "current.surface_pressure",
+        [hectopascals(Pressure::new(
+            current.surface_pressure,
+            pressure_unit,
+        ))],
+        &SURFACE_PRESSURE_HPA,
+    )
+}
+
+fn check_len(field: &'static str, len: usize, expected: usize) -> Result<(), ForecastError> {
+    if len != expected {
+        return Err(ForecastError::LengthMismatch {
+            field,
+            len,
+            expected,
+        });
+    }
+    Ok(())
+}
+
+/// Check that `times` are recent and consecutive ones are `step` seconds apart
+fn check_steps(
+    field: &'static str,
+    times: &[i64],
+    step: RangeInclusive<i64>,
+) -> Result<(), ForecastError> {
+    let recent = times.first().is_none_or(|&first| first >= MIN_TIMESTAMP);
+    if !recent
+        || times
+            .windows(2)
+            .any(|pair| !step.contains(&(pair[1] - pair[0])))
+    {
+        return Err(ForecastError::TimestampOutOfRange(field));
+    }
+    Ok(())
+}
+
+fn check_range<T: PartialOrd>(
+    field: &'static str,
+    values: impl IntoIterator<Item = T>,
+    range: &RangeInclusive<T>,
+) -> Result<(), ForecastError> {
+    if values.into_iter().any(|value| !range.contains(&value)) {
+        return Err(ForecastError::ImplausibleValue(field));
+    }
+    Ok(())
+}
+
+fn metres_per_second(speed: Speed) -> f32 {
+    speed.to(SpeedUnit::MetresPerSecond).value
+}
+
+fn millimetres(length: Length) -> f32 {
+    length.to(LengthUnit::Millimetre).value
+}
+
+fn hectopascals(pressure: Pressure) -> f32 {
+    pressure.to(PressureUnit::Hectopascal).value
+}